@@ -1,32 +1,41 @@
-#![feature(test)]
-
-use aoc_base::AoC;
-use std::error::Error;
+use aoc_base::{AoC, AoCError, Registry};
 
 pub struct DayX;
 
-impl DayX {
-}
-
 impl AoC<usize, usize> for DayX {
-    fn task_a(input: &str) -> Result<usize, Box<Error>> {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, AoCError> {
+        Ok(input.to_owned())
+    }
+
+    fn solve_a(_input: &String) -> Result<usize, AoCError> {
         unimplemented!();
     }
 
-    fn task_b(input: &str) -> Result<usize, Box<Error>> {
+    fn solve_b(_input: &String) -> Result<usize, AoCError> {
         unimplemented!();
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<DayX, _, _>(2018, 0 /* FIXME */, "FIXME");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use aoc_base::AoC;
     use super::DayX;
 
     const TEST_DATA: &str = "";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         //assert_eq!(DayX::task_a(TEST_DATA).unwrap(), FIXME);
@@ -37,13 +46,15 @@ mod tests {
         //assert_eq!(DayX::task_b(TEST_DATA).unwrap(), FIXME);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(|| DayX::task_a(TEST_DATA)));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(|| DayX::task_b(TEST_DATA)));
     }
 }
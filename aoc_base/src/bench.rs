@@ -0,0 +1,161 @@
+//! A small benchmarking harness which works on stable Rust.
+//!
+//! Benchmarks in the day crates are ignored tests, run them with
+//! `cargo test --release -- --ignored --nocapture bench`.
+
+use std::fmt::{self, Display, Formatter};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Number of samples collected per benchmark
+const SAMPLES: usize = 50;
+
+/// Target wall-clock time of a single sample
+const SAMPLE_TIME: Duration = Duration::from_millis(10);
+
+/// Summary of the time per iteration, in nanoseconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub mean: f64,
+    pub stddev: f64,
+    pub median: f64,
+    /// Samples outside of the inner fences, i.e. 1.5 IQR from the quartiles
+    pub outliers: usize,
+    /// Number of iterations in total
+    pub iterations: u64,
+}
+
+impl Stats {
+    /// Compute statistics from a set of per-iteration times
+    pub fn from_samples(samples: &[f64], iterations: u64) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let n = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / n;
+        let variance = if sorted.len() > 1 {
+            sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+
+        let (q1, median, q3) = (
+            percentile(&sorted, 25.0),
+            percentile(&sorted, 50.0),
+            percentile(&sorted, 75.0),
+        );
+        let iqr = q3 - q1;
+        let (low, high) = (q1 - 1.5 * iqr, q3 + 1.5 * iqr);
+        let outliers = sorted.iter().filter(|&&s| s < low || s > high).count();
+
+        Stats {
+            mean,
+            stddev: variance.sqrt(),
+            median,
+            outliers,
+            iterations,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:>10} ± {:>10}   median {:>10}   {} outliers",
+            format_nanos(self.mean),
+            format_nanos(self.stddev),
+            format_nanos(self.median),
+            self.outliers
+        )
+    }
+}
+
+/// Linear interpolation between the closest ranks of a sorted slice
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = pct / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    let fraction = rank - lower as f64;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+}
+
+pub fn format_nanos(ns: f64) -> String {
+    if ns >= 1e9 {
+        format!("{:.2}s", ns / 1e9)
+    } else if ns >= 1e6 {
+        format!("{:.2}ms", ns / 1e6)
+    } else if ns >= 1e3 {
+        format!("{:.2}µs", ns / 1e3)
+    } else {
+        format!("{:.2}ns", ns)
+    }
+}
+
+#[derive(Default)]
+pub struct Bencher {
+    stats: Option<Stats>,
+}
+
+impl Bencher {
+    pub fn new() -> Bencher {
+        Bencher::default()
+    }
+
+    /// Repeatedly run `inner`, collecting timing samples
+    pub fn iter<T, F: FnMut() -> T>(&mut self, mut inner: F) {
+        // Run once to estimate how many iterations fit in a sample
+        let start = Instant::now();
+        black_box(inner());
+        let single = start.elapsed();
+        let per_sample = if single >= SAMPLE_TIME {
+            1
+        } else {
+            let nanos = single.as_nanos().max(1);
+            (SAMPLE_TIME.as_nanos() / nanos).max(1) as u64
+        };
+
+        let mut samples = Vec::with_capacity(SAMPLES);
+        for _ in 0..SAMPLES {
+            let start = Instant::now();
+            for _ in 0..per_sample {
+                black_box(inner());
+            }
+            samples.push(start.elapsed().as_nanos() as f64 / per_sample as f64);
+        }
+
+        self.stats = Some(Stats::from_samples(&samples, per_sample * SAMPLES as u64));
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        self.stats
+    }
+}
+
+/// Run a benchmark and print its results
+pub fn bench<F: FnOnce(&mut Bencher)>(name: &str, f: F) -> Option<Stats> {
+    let mut b = Bencher::new();
+    f(&mut b);
+    match b.stats() {
+        Some(stats) => println!("{:30} {}", name, stats),
+        None => println!("{:30} did not call Bencher::iter", name),
+    }
+    b.stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut samples: Vec<f64> = (1..=9).map(|n| n as f64).collect();
+        samples.push(100.0);
+        let stats = Stats::from_samples(&samples, 10);
+        assert_eq!(stats.mean, 14.5);
+        assert_eq!(stats.median, 5.5);
+        assert_eq!(stats.outliers, 1);
+    }
+}
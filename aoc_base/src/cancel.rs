@@ -0,0 +1,104 @@
+use crate::AoCError;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Cancel>> = const { RefCell::new(None) };
+}
+
+/// Lets a solve be stopped from the outside, either explicitly or once a
+/// timeout has passed. Cancelling is cooperative: solvers check for it with
+/// [`check_cancelled`] in their long loops, and a solver which never does runs
+/// to the end.
+#[derive(Clone, Debug, Default)]
+pub struct Cancel {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl Cancel {
+    pub fn new() -> Cancel {
+        Cancel::default()
+    }
+
+    /// A token which cancels itself once `timeout` has passed
+    pub fn with_timeout(timeout: Duration) -> Cancel {
+        Cancel {
+            deadline: Some((Instant::now() + timeout, timeout)),
+            ..Cancel::default()
+        }
+    }
+
+    /// Cancel the solves using this token, or any clone of it
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self.deadline.is_some_and(|(at, _)| Instant::now() >= at)
+    }
+
+    /// Fail with [`AoCError::Cancelled`] if the token has been cancelled
+    pub fn check(&self) -> Result<(), AoCError> {
+        if !self.is_cancelled() {
+            return Ok(());
+        }
+        let reason = match self.deadline {
+            Some((at, timeout)) if Instant::now() >= at => {
+                format!("Timed out after {:?}", timeout)
+            }
+            _ => "Cancelled".to_string(),
+        };
+        Err(AoCError::Cancelled(reason))
+    }
+
+    /// Run `f` with this as the token seen by [`check_cancelled`] on this
+    /// thread. Threads started by `f`, e.g. by rayon, don't see it.
+    pub fn run<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        let previous = CURRENT.with(|current| current.replace(Some(self.clone())));
+        let result = f();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+        result
+    }
+}
+
+/// Fail with [`AoCError::Cancelled`] if the solve running on this thread has
+/// been cancelled, see [`Cancel::run`]. Outside of one this always succeeds.
+pub fn check_cancelled() -> Result<(), AoCError> {
+    CURRENT.with(|current| match &*current.borrow() {
+        Some(cancel) => cancel.check(),
+        None => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel() {
+        assert!(check_cancelled().is_ok());
+
+        let cancel = Cancel::new();
+        assert!(cancel.run(check_cancelled).is_ok());
+        cancel.clone().cancel();
+        match cancel.run(check_cancelled) {
+            Err(AoCError::Cancelled(reason)) => assert_eq!(reason, "Cancelled"),
+            r => panic!("Expected the solve to be cancelled, got {:?}", r),
+        }
+        // The token only applies within run
+        assert!(check_cancelled().is_ok());
+
+        let timeout = Cancel::with_timeout(Duration::from_millis(0));
+        match timeout.run(check_cancelled) {
+            Err(AoCError::Cancelled(reason)) => assert_eq!(reason, "Timed out after 0ns"),
+            r => panic!("Expected the solve to time out, got {:?}", r),
+        }
+        assert!(Cancel::with_timeout(Duration::from_secs(60))
+            .check()
+            .is_ok());
+    }
+}
@@ -0,0 +1,107 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+
+#[derive(Debug)]
+pub enum AoCError {
+    /// The input is malformed at the given (1-indexed) line and column
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// The input is well-formed, but has no answer
+    NoSolution(String),
+
+    /// The input is well-formed, but doesn't have the shape the puzzle describes
+    InvalidInput(String),
+
+    /// The solve was stopped from the outside, see [`crate::Cancel`]
+    Cancelled(String),
+
+    /// The solver panicked, with the message of the panic
+    Panicked(String),
+
+    Io(io::Error),
+}
+
+impl AoCError {
+    pub fn parse<M: Into<String>>(line: usize, column: usize, message: M) -> AoCError {
+        AoCError::Parse {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Create a parse error pointing at `at`, which must be a slice of `input`
+    pub fn parse_at<M: Into<String>>(input: &str, at: &str, message: M) -> AoCError {
+        let offset = (at.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .expect("parse_at called with a str which isn't part of the input");
+
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        AoCError::parse(line, column, message)
+    }
+
+    pub fn no_solution<M: Into<String>>(message: M) -> AoCError {
+        AoCError::NoSolution(message.into())
+    }
+
+    pub fn invalid_input<M: Into<String>>(message: M) -> AoCError {
+        AoCError::InvalidInput(message.into())
+    }
+}
+
+impl Display for AoCError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AoCError::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "Parse error at line {}, column {}: {}", line, column, message),
+            AoCError::NoSolution(message) => write!(f, "No solution: {}", message),
+            AoCError::InvalidInput(message) => write!(f, "Invalid input: {}", message),
+            AoCError::Cancelled(reason) => write!(f, "{}", reason),
+            AoCError::Panicked(message) => write!(f, "Panicked: {}", message),
+            AoCError::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl Error for AoCError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AoCError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AoCError {
+    fn from(e: io::Error) -> AoCError {
+        AoCError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_at() {
+        let input = "+1\n-2\n+3x\n";
+        let at = &input[8..9];
+        assert_eq!(at, "x");
+        match AoCError::parse_at(input, at, "Not a number") {
+            AoCError::Parse { line, column, .. } => assert_eq!((line, column), (3, 3)),
+            e => panic!("Unexpected error: {}", e),
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Examples of a day with their expected answers, kept in a `fixtures.toml`
+//! next to the `Cargo.toml` of its crate:
+//!
+//! ```toml
+//! [[example]]
+//! name = "two workers"        # optional, defaults to "example N"
+//! input = '''
+//! Step C must be finished before step A can begin.
+//! '''
+//! a = "C"                     # either part may be left out
+//! b = 61
+//! params = { workers = 2, base_time = 60 }
+//! ```
+//!
+//! Answers are compared as text, ignoring trailing newlines.
+
+use crate::{Params, Part, Registry, Solver};
+use serde_derive::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use toml::Value;
+
+#[derive(Deserialize)]
+struct FixtureFile {
+    #[serde(default)]
+    example: Vec<RawFixture>,
+}
+
+#[derive(Deserialize)]
+struct RawFixture {
+    name: Option<String>,
+    input: String,
+    a: Option<Value>,
+    b: Option<Value>,
+    #[serde(default)]
+    params: BTreeMap<String, Value>,
+}
+
+/// One example input of a day
+#[derive(Debug, PartialEq)]
+pub struct Fixture {
+    pub name: String,
+    pub input: String,
+    pub a: Option<String>,
+    pub b: Option<String>,
+    pub params: Params,
+}
+
+impl Fixture {
+    pub fn expected(&self, part: Part) -> Option<&String> {
+        match part {
+            Part::A => self.a.as_ref(),
+            Part::B => self.b.as_ref(),
+        }
+    }
+}
+
+/// Numbers may be written as such, everything else has to be a string
+fn as_text(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        value => value.to_string(),
+    }
+}
+
+pub fn parse_fixtures(text: &str) -> Result<Vec<Fixture>, Box<dyn Error>> {
+    let file: FixtureFile = toml::from_str(text)?;
+    Ok(file
+        .example
+        .into_iter()
+        .enumerate()
+        .map(|(i, raw)| {
+            let mut params = Params::new();
+            for (name, value) in raw.params {
+                params.set(&name, as_text(value));
+            }
+            Fixture {
+                name: raw.name.unwrap_or_else(|| format!("example {}", i + 1)),
+                input: raw.input,
+                a: raw.a.map(as_text),
+                b: raw.b.map(as_text),
+                params,
+            }
+        })
+        .collect())
+}
+
+/// Run every fixture against a solver, describing each mismatch and, for
+/// fixtures of both parts, each param which neither part looked up
+pub fn run_fixtures(solver: &Solver, fixtures: &[Fixture]) -> Vec<String> {
+    let mut failures = vec![];
+    for fixture in fixtures {
+        let parsed = match solver.parse(&fixture.input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                failures.push(format!("{}: {}", fixture.name, e));
+                continue;
+            }
+        };
+        let mut skipped = false;
+        for &part in &[Part::A, Part::B] {
+            let expected = match fixture.expected(part) {
+                Some(expected) => expected.trim_end_matches('\n'),
+                None => {
+                    skipped = true;
+                    continue;
+                }
+            };
+            match solver.solve_with(part, &parsed, &fixture.params) {
+                Ok(answer) if answer.trim_end_matches('\n') == expected => {}
+                Ok(answer) => failures.push(format!(
+                    "{} part {}: expected {:?}, got {:?}",
+                    fixture.name, part, expected, answer
+                )),
+                Err(e) => failures.push(format!("{} part {}: {}", fixture.name, part, e)),
+            }
+        }
+        // The part left out may be the one reading a param
+        let unused = fixture.params.unused();
+        if !skipped && !unused.is_empty() {
+            failures.push(format!(
+                "{}: params not used by the solver: {}",
+                fixture.name,
+                unused.join(", ")
+            ));
+        }
+    }
+    failures
+}
+
+/// Check the fixtures of a day crate against the solver it registers,
+/// panicking with every mismatch. Meant to be called from a `#[test]`.
+pub fn test_day(register: fn(&mut Registry), fixtures: &str) {
+    let mut registry = Registry::new();
+    register(&mut registry);
+    let solver = registry.iter().next().expect("The day registered no solver");
+    let fixtures = parse_fixtures(fixtures).expect("Invalid fixtures.toml");
+
+    let failures = run_fixtures(solver, &fixtures);
+    if !failures.is_empty() {
+        panic!("{} failed:\n{}", solver.name(), failures.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AoC, AoCError};
+
+    /// Sums the numbers of its input, part B multiplies the sum by a parameter
+    struct Sum;
+
+    impl AoC<i32, i32> for Sum {
+        type Input = Vec<i32>;
+
+        fn parse(input: &str) -> Result<Vec<i32>, AoCError> {
+            input
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| AoCError::parse(1, 1, "NaN")))
+                .collect()
+        }
+
+        fn solve_a(input: &Vec<i32>) -> Result<i32, AoCError> {
+            Ok(input.iter().sum())
+        }
+
+        fn solve_b(input: &Vec<i32>) -> Result<i32, AoCError> {
+            Self::solve_b_with(input, &Params::new())
+        }
+
+        fn solve_b_with(input: &Vec<i32>, params: &Params) -> Result<i32, AoCError> {
+            Ok(input.iter().sum::<i32>() * params.get("factor", 10)?)
+        }
+    }
+
+    fn register(registry: &mut Registry) {
+        registry.register::<Sum, _, _>(2018, 1, "Sum");
+    }
+
+    const FIXTURES: &str = r#"
+        [[example]]
+        input = '''
+        1 2
+        3
+        '''
+        a = 6
+        b = "60"
+
+        [[example]]
+        name = "doubled"
+        input = "1 1"
+        b = 4
+        params = { factor = 2 }
+    "#;
+
+    #[test]
+    fn test_parse_fixtures() {
+        let fixtures = parse_fixtures(FIXTURES).unwrap();
+        assert_eq!(fixtures.len(), 2);
+        assert_eq!(fixtures[0].name, "example 1");
+        assert_eq!(fixtures[0].a.as_deref(), Some("6"));
+        assert_eq!(fixtures[1].name, "doubled");
+        assert_eq!(fixtures[1].a, None);
+        assert_eq!(fixtures[1].params.get("factor", 10).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_run_fixtures() {
+        test_day(register, FIXTURES);
+
+        let mut registry = Registry::new();
+        register(&mut registry);
+        let solver = registry.get(2018, 1).unwrap();
+        let wrong = parse_fixtures("[[example]]\ninput = \"1 x\"\n\n[[example]]\ninput = \"2\"\na = 3")
+            .unwrap();
+        let failures = run_fixtures(solver, &wrong);
+        assert_eq!(failures.len(), 2);
+        assert!(failures[1].starts_with("example 2 part A: expected \"3\", got \"2\""));
+
+        let misspelled = parse_fixtures(
+            "[[example]]\ninput = \"2\"\na = 2\nb = 4\nparams = { factr = 2 }",
+        )
+        .unwrap();
+        assert_eq!(
+            run_fixtures(solver, &misspelled),
+            vec![
+                "example 1 part B: expected \"4\", got \"20\"",
+                "example 1: params not used by the solver: factr",
+            ]
+        );
+        // Only part B reads the factor
+        let only_a = parse_fixtures("[[example]]\ninput = \"2\"\na = 2\nparams = { factor = 2 }")
+            .unwrap();
+        assert!(run_fixtures(solver, &only_a).is_empty());
+    }
+}
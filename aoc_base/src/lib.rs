@@ -1,11 +1,46 @@
-use std::error::Error;
+pub mod bench;
+mod cancel;
+mod error;
+pub mod fixture;
+mod params;
+mod registry;
+
 use std::fmt::Display;
 
+pub use crate::cancel::{check_cancelled, Cancel};
+pub use crate::error::AoCError;
+pub use crate::params::Params;
+pub use crate::registry::{Parsed, Part, Registry, Solver};
+
 pub trait AoC<RA, RB>
     where RA: Display,
           RB: Display,
 {
-    fn task_a(input: &str) -> Result<RA, Box<Error>>;
-    fn task_b(input: &str) -> Result<RB, Box<Error>>;
-}
+    /// The input of both parts, after parsing
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, AoCError>;
+    fn solve_a(input: &Self::Input) -> Result<RA, AoCError>;
+    fn solve_b(input: &Self::Input) -> Result<RB, AoCError>;
 
+    /// Solve part A with some of its constants overridden, see [`Params`].
+    /// Only days whose examples use other constants than the real puzzle need this.
+    fn solve_a_with(input: &Self::Input, _params: &Params) -> Result<RA, AoCError> {
+        Self::solve_a(input)
+    }
+
+    /// Solve part B with some of its constants overridden, see [`Params`]
+    fn solve_b_with(input: &Self::Input, _params: &Params) -> Result<RB, AoCError> {
+        Self::solve_b(input)
+    }
+
+    /// Parse the input and solve part A
+    fn task_a(input: &str) -> Result<RA, AoCError> {
+        Self::solve_a(&Self::parse(input)?)
+    }
+
+    /// Parse the input and solve part B
+    fn task_b(input: &str) -> Result<RB, AoCError> {
+        Self::solve_b(&Self::parse(input)?)
+    }
+}
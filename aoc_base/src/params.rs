@@ -0,0 +1,89 @@
+use crate::AoCError;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// Named values overriding constants of a puzzle, as its examples sometimes
+/// use e.g. fewer workers or a smaller threshold than the real input
+#[derive(Debug, Default)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+    /// Names looked up by a solver, to catch misspelled parameters
+    used: Mutex<BTreeSet<String>>,
+}
+
+impl Clone for Params {
+    fn clone(&self) -> Params {
+        Params {
+            values: self.values.clone(),
+            used: Mutex::new(self.used.lock().unwrap_or_else(|e| e.into_inner()).clone()),
+        }
+    }
+}
+
+impl PartialEq for Params {
+    fn eq(&self, other: &Params) -> bool {
+        self.values == other.values
+    }
+}
+
+impl Params {
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    pub fn set<V: ToString>(&mut self, name: &str, value: V) {
+        self.values.insert(name.to_owned(), value.to_string());
+    }
+
+    /// The value of a parameter, or `default` if it isn't overridden
+    pub fn get<T>(&self, name: &str, default: T) -> Result<T, AoCError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.used
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_owned());
+        match self.values.get(name) {
+            Some(value) => value.parse().map_err(|e| {
+                AoCError::invalid_input(format!("Invalid parameter {}={}: {}", name, value, e))
+            }),
+            None => Ok(default),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// The parameters that were set but never looked up
+    pub fn unused(&self) -> Vec<&str> {
+        let used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        self.values
+            .keys()
+            .filter(|name| !used.contains(*name))
+            .map(|name| name.as_str())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let mut params = Params::new();
+        params.set("workers", 2);
+        assert_eq!(params.get("workers", 5).unwrap(), 2);
+        assert_eq!(params.get("base_time", 60).unwrap(), 60);
+        params.set("workers", "many");
+        assert!(params.get("workers", 5).is_err());
+
+        params.set("worker", 2);
+        assert_eq!(params.unused(), vec!["worker"]);
+    }
+}
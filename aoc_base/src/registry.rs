@@ -0,0 +1,203 @@
+use crate::{AoC, AoCError, Params};
+use std::any::Any;
+use std::fmt::{self, Display, Formatter};
+use std::sync::Arc;
+
+/// The parsed input of a day, as produced by [`Solver::parse`]. Clones share
+/// the input, so that parts can be solved on other threads.
+#[derive(Clone)]
+pub struct Parsed(Arc<dyn Any + Send + Sync>);
+
+type ParseFn = fn(&str) -> Result<Parsed, AoCError>;
+type SolveFn = fn(&Parsed, &Params) -> Result<String, AoCError>;
+
+/// One of the two parts of a day
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    /// The level number the Advent of Code server uses for this part
+    pub fn level(self) -> u8 {
+        match self {
+            Part::A => 1,
+            Part::B => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Part::A => write!(f, "A"),
+            Part::B => write!(f, "B"),
+        }
+    }
+}
+
+/// A type-erased solver for one day
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub year: u32,
+    pub day: u8,
+    pub title: &'static str,
+    parse: ParseFn,
+    solve_a: SolveFn,
+    solve_b: SolveFn,
+}
+
+impl Solver {
+    pub fn new<D, RA, RB>(year: u32, day: u8, title: &'static str) -> Solver
+    where
+        D: AoC<RA, RB>,
+        D::Input: Send + Sync + 'static,
+        RA: Display,
+        RB: Display,
+    {
+        Solver {
+            year,
+            day,
+            title,
+            parse: |input| Ok(Parsed(Arc::new(D::parse(input)?))),
+            solve_a: |input, params| {
+                Ok(D::solve_a_with(input.downcast::<D::Input>(), params)?.to_string())
+            },
+            solve_b: |input, params| {
+                Ok(D::solve_b_with(input.downcast::<D::Input>(), params)?.to_string())
+            },
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Parsed, AoCError> {
+        (self.parse)(input)
+    }
+
+    /// Solve a part, given the input parsed by this same solver
+    pub fn solve(&self, part: Part, input: &Parsed) -> Result<String, AoCError> {
+        self.solve_with(part, input, &Params::new())
+    }
+
+    /// Solve a part with some constants of the puzzle overridden
+    pub fn solve_with(
+        &self,
+        part: Part,
+        input: &Parsed,
+        params: &Params,
+    ) -> Result<String, AoCError> {
+        match part {
+            Part::A => (self.solve_a)(input, params),
+            Part::B => (self.solve_b)(input, params),
+        }
+    }
+
+    pub fn run(&self, part: Part, input: &str) -> Result<String, AoCError> {
+        self.solve(part, &self.parse(input)?)
+    }
+
+    /// The name used for this day on the command line, e.g. `day01`
+    pub fn name(&self) -> String {
+        format!("day{:02}", self.day)
+    }
+}
+
+impl Parsed {
+    fn downcast<T: 'static>(&self) -> &T {
+        self.0
+            .downcast_ref()
+            .expect("Input parsed by a different solver")
+    }
+}
+
+/// All known solvers, ordered by year and day
+#[derive(Default)]
+pub struct Registry {
+    solvers: Vec<Solver>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    pub fn register<D, RA, RB>(&mut self, year: u32, day: u8, title: &'static str)
+    where
+        D: AoC<RA, RB>,
+        D::Input: Send + Sync + 'static,
+        RA: Display,
+        RB: Display,
+    {
+        let solver = Solver::new::<D, RA, RB>(year, day, title);
+        match self
+            .solvers
+            .binary_search_by_key(&(year, day), |s| (s.year, s.day))
+        {
+            Ok(_) => panic!("Day {} of {} registered twice", day, year),
+            Err(i) => self.solvers.insert(i, solver),
+        }
+    }
+
+    pub fn get(&self, year: u32, day: u8) -> Option<&Solver> {
+        self.solvers.iter().find(|s| (s.year, s.day) == (year, day))
+    }
+
+    pub fn find(&self, year: u32, name: &str) -> Option<&Solver> {
+        self.year(year).find(|s| s.name() == name)
+    }
+
+    /// The solvers of every year
+    pub fn iter(&self) -> impl Iterator<Item = &Solver> {
+        self.solvers.iter()
+    }
+
+    /// The solvers of a single year
+    pub fn year(&self, year: u32) -> impl Iterator<Item = &Solver> {
+        self.solvers.iter().filter(move |s| s.year == year)
+    }
+
+    /// The years with at least one solver, in order
+    pub fn years(&self) -> Vec<u32> {
+        let mut years: Vec<u32> = self.solvers.iter().map(|s| s.year).collect();
+        years.dedup();
+        years
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Echo;
+
+    impl AoC<String, usize> for Echo {
+        type Input = String;
+
+        fn parse(input: &str) -> Result<String, AoCError> {
+            Ok(input.to_owned())
+        }
+
+        fn solve_a(input: &String) -> Result<String, AoCError> {
+            Ok(input.clone())
+        }
+
+        fn solve_b(input: &String) -> Result<usize, AoCError> {
+            Ok(input.len())
+        }
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::new();
+        registry.register::<Echo, _, _>(2019, 1, "Later");
+        registry.register::<Echo, _, _>(2018, 2, "Second");
+        registry.register::<Echo, _, _>(2018, 1, "First");
+
+        assert_eq!(registry.years(), vec![2018, 2019]);
+        let days: Vec<_> = registry.year(2018).map(|s| s.title).collect();
+        assert_eq!(days, vec!["First", "Second"]);
+        assert_eq!(registry.find(2019, "day01").unwrap().title, "Later");
+        assert!(registry.get(2019, 2).is_none());
+        assert_eq!(registry.get(2018, 2).unwrap().run(Part::B, "abc").unwrap(), "3");
+    }
+}
@@ -1,32 +1,40 @@
-#![feature(test)]
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use std::collections::HashSet;
-use std::error::Error;
 
 pub struct Day01;
 
-impl Day01 {
-    fn parse_freqs<'a>(input: &'a str) -> impl Iterator<Item = i32> + 'a {
-        let iter = input.lines().filter_map(|s| s.parse::<i32>().ok());
-        Box::new(iter)
+impl AoC<i32, i32> for Day01 {
+    type Input = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Vec<i32>, AoCError> {
+        input
+            .lines()
+            .map(|s| {
+                s.parse::<i32>()
+                    .map_err(|e| AoCError::parse_at(input, s, e.to_string()))
+            })
+            .collect()
     }
-}
 
-impl AoC<i32, i32> for Day01 {
     /// Sum the frequencies
-    fn task_a(input: &str) -> Result<i32, Box<Error>> {
-        let sum = Self::parse_freqs(input).sum();
+    fn solve_a(freqs: &Vec<i32>) -> Result<i32, AoCError> {
+        let sum = freqs.iter().sum();
 
         Ok(sum)
     }
 
     /// Find the first duplicate frequency
-    fn task_b(input: &str) -> Result<i32, Box<Error>> {
-        let pattern: Vec<i32> = Self::parse_freqs(input).collect();
+    fn solve_b(pattern: &Vec<i32>) -> Result<i32, AoCError> {
+        if pattern.is_empty() {
+            return Err(AoCError::invalid_input("No frequency changes"));
+        }
+
         let mut history: HashSet<i32> = HashSet::with_capacity(pattern.len());
 
+        // Loops forever if no frequency repeats, unless cancelled
         let mut last = 0;
         loop {
+            aoc_base::check_cancelled()?;
             for num in pattern.iter() {
                 if !history.insert(last) {
                     return Ok(last);
@@ -37,36 +45,50 @@ impl AoC<i32, i32> for Day01 {
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day01, _, _>(2018, 1, "Chronal Calibration");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::{bench, Bencher};
     use super::*;
     use aoc_base::AoC;
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         let data = "+4\n-6\n+33";
-        assert_eq!(Day01::task_a(&data).unwrap(), 31);
+        assert_eq!(Day01::task_a(data).unwrap(), 31);
     }
 
-    const TEST_DATA_B: &[(&str, i32)] = &[
-        ("1\n-1", 0),
-        //(&["+3", "+3", "+4", "-2", "-4"], 10),
-        //(&["-6", "+3", "+8", "+5", "-6"], 5),
-        //(&["+7", "+7", "-2", "-7", "-4"], 14),
-    ];
+    #[test]
+    fn test_parse_error() {
+        match Day01::task_a("+4\n-6x\n+33") {
+            Err(AoCError::Parse { line, column, .. }) => assert_eq!((line, column), (2, 1)),
+            r => panic!("Expected a parse error, got {:?}", r),
+        }
+    }
 
     #[test]
-    fn test_b() {
-        for (i, r) in TEST_DATA_B {
-            assert_eq!(Day01::task_b(*i).unwrap(), r.clone());
+    fn test_b_cancelled() {
+        // No frequency ever repeats
+        let cancel = aoc_base::Cancel::with_timeout(std::time::Duration::from_millis(10));
+        match cancel.run(|| Day01::task_b("+1")) {
+            Err(AoCError::Cancelled(_)) => {}
+            r => panic!("Expected task_b to time out, got {:?}", r),
         }
     }
 
-    #[bench]
-    fn bench_sum_4(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_sum_4() {
+        bench("bench_sum_4", |b| b.iter(|| Day01::task_b("+1\n-1").unwrap()));
     }
 
     fn bench_find_dup(b: &mut Bencher, steps: i32) {
@@ -77,18 +99,21 @@ mod tests {
         })
     }
 
-    #[bench]
-    fn bench_find_dup_100(b: &mut Bencher) {
-        bench_find_dup(b, 100);
+    #[test]
+    #[ignore]
+    fn bench_find_dup_100() {
+        bench("bench_find_dup_100", |b| bench_find_dup(b, 100));
     }
 
-    #[bench]
-    fn bench_find_dup_1000(b: &mut Bencher) {
-        bench_find_dup(b, 1000);
+    #[test]
+    #[ignore]
+    fn bench_find_dup_1000() {
+        bench("bench_find_dup_1000", |b| bench_find_dup(b, 1000));
     }
 
-    #[bench]
-    fn bench_find_dup_10000(b: &mut Bencher) {
-        bench_find_dup(b, 10000);
+    #[test]
+    #[ignore]
+    fn bench_find_dup_10000() {
+        bench("bench_find_dup_10000", |b| bench_find_dup(b, 10000));
     }
 }
@@ -1,15 +1,20 @@
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use std::collections::HashMap;
-use std::error::Error;
 
 pub struct Day02;
 
 impl AoC<i32, String> for Day02 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>, AoCError> {
+        Ok(input.lines().map(|s| s.to_owned()).collect())
+    }
+
     /// Compute a checksum for the ids
-    fn task_a(inputs: &str) -> Result<i32, Box<Error>> {
+    fn solve_a(ids: &Vec<String>) -> Result<i32, AoCError> {
         let mut twos = 0;
         let mut threes = 0;
-        for s in inputs.lines() {
+        for s in ids {
             let mut has_two = false;
             let mut has_three = false;
             let mut map = HashMap::new();
@@ -34,11 +39,11 @@ impl AoC<i32, String> for Day02 {
             }
         }
 
-        return Ok(twos * threes);
+        Ok(twos * threes)
     }
 
     /// Find the one id which only differs by one character to another id
-    fn task_b(ids: &str) -> Result<String, Box<Error>> {
+    fn solve_b(ids: &Vec<String>) -> Result<String, AoCError> {
         // Return a vec of indices for the differing elements
         // Will return strange results if s1 and s2 are of different lengths
         let diffs = |s1: &str, s2: &str| -> Vec<usize> {
@@ -54,27 +59,37 @@ impl AoC<i32, String> for Day02 {
             ds
         };
 
-        for (i, s1) in ids.lines().enumerate() {
-            for s2 in ids.lines().take(i) {
-                let ds = diffs(s1.as_ref(), s2.as_ref());
+        for (i, s1) in ids.iter().enumerate() {
+            for s2 in ids.iter().take(i) {
+                let ds = diffs(s1, s2);
                 if ds.len() == 1 {
-                    let mut id = String::from(s1.as_ref());
+                    let mut id = s1.clone();
                     id.remove(ds[0]);
                     return Ok(id);
                 }
             }
         }
 
-        Ok("".into()) // TODO
+        Err(AoCError::no_solution("No two ids differ by exactly one character"))
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day02, _, _>(2018, 2, "Inventory Management System");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use aoc_base::AoC;
 
     const TEST_DATA_A: &str = "abcde\nfghij\nklmno\npqrst\nfguij\naxcye\nwvxyz";
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_find_similar_id() {
         assert_eq!(Day02::task_b(TEST_DATA_A).unwrap(), "fgij");
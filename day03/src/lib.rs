@@ -1,49 +1,63 @@
-#![feature(test)]
-
 mod parser;
 
 use crate::parser::{RectParser, Rectangle, Rule};
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use from_pest::FromPest;
+use pest::error::{ErrorVariant, LineColLocation};
 use pest::Parser;
 use std::collections::{HashMap, HashSet};
-use std::error::Error;
 use std::iter::repeat;
 
 pub struct Day03;
 
 impl Day03 {
     fn parse_squares<'a>(
-        inputs: &'a str,
-    ) -> Result<impl Iterator<Item = (i32, impl Iterator<Item = (i32, i32)>)> + 'a, Box<Error>>
-    {
-        let iter = inputs
-            .lines()
-            .map(|s| s.to_owned())
-            .map(|s| {
-                let mut p = RectParser::parse(Rule::rect, &s).unwrap();
-                Rectangle::from_pest(&mut p).unwrap()
-            })
-            .map(|r| {
-                let (x, y) = (r.coord.x.v, r.coord.y.v);
-                let (w, h) = (r.size.w.v, r.size.h.v);
-                let v: Vec<_> = (x..(x + w))
-                    .flat_map(|i| repeat(i).zip(y..(y + h)))
-                    .collect();
-
-                (r.id.value.v, v.into_iter())
-            });
-
-        Ok(iter)
+        rects: &'a [Rectangle],
+    ) -> impl Iterator<Item = (i32, impl Iterator<Item = (i32, i32)>)> + 'a {
+        rects.iter().map(|r| {
+            let (x, y) = (r.coord.x.v, r.coord.y.v);
+            let (w, h) = (r.size.w.v, r.size.h.v);
+            let v: Vec<_> = (x..(x + w))
+                .flat_map(|i| repeat(i).zip(y..(y + h)))
+                .collect();
+
+            (r.id.value.v, v.into_iter())
+        })
     }
 }
 
 impl AoC<usize, i32> for Day03 {
+    type Input = Vec<Rectangle>;
+
+    fn parse(inputs: &str) -> Result<Vec<Rectangle>, AoCError> {
+        inputs
+            .lines()
+            .enumerate()
+            .map(|(i, s)| {
+                let mut p = RectParser::parse(Rule::rect, s).map_err(|e| {
+                    let column = match e.line_col {
+                        LineColLocation::Pos((_, c)) => c,
+                        LineColLocation::Span((_, c), _) => c,
+                    };
+                    let message = match e.variant {
+                        ErrorVariant::ParsingError { positives, .. } => {
+                            format!("Expected one of {:?}", positives)
+                        }
+                        ErrorVariant::CustomError { message } => message,
+                    };
+                    AoCError::parse(i + 1, column, message)
+                })?;
+                Rectangle::from_pest(&mut p)
+                    .map_err(|_| AoCError::parse(i + 1, 1, "Not a valid rectangle"))
+            })
+            .collect()
+    }
+
     /// Get number of overlapping cells
-    fn task_a(inputs: &str) -> Result<usize, Box<Error>> {
+    fn solve_a(rects: &Vec<Rectangle>) -> Result<usize, AoCError> {
         let mut overlapping: usize = 0;
         let mut map: HashMap<(i32, i32), usize> = HashMap::with_capacity(30 * 30);
-        for (_, cells) in Self::parse_squares(inputs)? {
+        for (_, cells) in Self::parse_squares(rects) {
             for (i, j) in cells {
                 let val = map.entry((i, j)).or_insert(0);
                 *val += 1;
@@ -57,10 +71,10 @@ impl AoC<usize, i32> for Day03 {
     }
 
     /// Find the one box which doesn't overlap
-    fn task_b(inputs: &str) -> Result<i32, Box<Error>> {
+    fn solve_b(rects: &Vec<Rectangle>) -> Result<i32, AoCError> {
         let mut possible_claims: HashSet<i32> = HashSet::new();
         let mut map: HashMap<(i32, i32), Vec<i32>> = HashMap::with_capacity(30 * 30);
-        for (id, cells) in Self::parse_squares(inputs)? {
+        for (id, cells) in Self::parse_squares(rects) {
             let mut possible = true;
             for (i, j) in cells {
                 let existing_claims = map.entry((i, j)).or_insert(vec![]);
@@ -76,23 +90,31 @@ impl AoC<usize, i32> for Day03 {
             }
         }
 
-        Ok(possible_claims
+        possible_claims
             .iter()
-            .next()
-            .map(|id| id.clone())
-            .ok_or("No safe claim found")?)
+            .next().copied()
+            .ok_or_else(|| AoCError::no_solution("No safe claim found"))
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day03, _, _>(2018, 3, "No Matter How You Slice It");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::*;
     use aoc_base::AoC;
 
     const TEST_DATA: &str = "#1 @ 1,3: 4x4\n#2 @ 3,1: 4x4\n#3 @ 5,5: 2x2";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         assert_eq!(Day03::task_a(TEST_DATA).unwrap(), 4);
@@ -103,13 +125,15 @@ mod tests {
         assert_eq!(Day03::task_b(TEST_DATA).unwrap(), 3);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
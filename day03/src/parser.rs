@@ -6,7 +6,7 @@ use pest_derive::*;
 #[grammar = "rectangles.pest"]
 pub struct RectParser;
 
-fn span_into_str(span: Span) -> &str {
+fn span_into_str(span: Span<'_>) -> &str {
     span.as_str()
 }
 
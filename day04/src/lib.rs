@@ -1,13 +1,13 @@
-#![feature(test)]
-
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use chrono::{prelude::*, Duration};
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::error::Error;
 
 pub struct Day04;
 
+/// Minutes each guard spent asleep, and their total
+type Schedule = HashMap<usize, ([usize; 60], usize)>;
+
 #[derive(Debug, PartialEq)]
 enum GuardState {
     Awake,
@@ -15,45 +15,53 @@ enum GuardState {
 }
 
 impl Day04 {
-    fn parse_inputs(inputs: &str) -> Vec<(NaiveDateTime, usize, GuardState)> {
-        let mut vec: Vec<String> = inputs.lines().map(|s| s.to_owned()).collect();
+    fn parse_inputs(inputs: &str) -> Result<Vec<(NaiveDateTime, usize, GuardState)>, AoCError> {
+        let mut vec: Vec<&str> = inputs.lines().collect();
         vec.sort();
 
         let mut last_id: usize = 0;
         vec.iter()
             .map(|l| {
-                (
-                    Utc.datetime_from_str(&l[1..17], "%Y-%m-%d %H:%M")
-                        .unwrap()
-                        .naive_utc(),
-                    &l[19..],
-                )
+                let time = l
+                    .get(1..17)
+                    .and_then(|t| Utc.datetime_from_str(t, "%Y-%m-%d %H:%M").ok())
+                    .ok_or_else(|| AoCError::parse_at(inputs, l, "Expected a timestamp"))?
+                    .naive_utc();
+                let r = l
+                    .get(19..)
+                    .ok_or_else(|| AoCError::parse_at(inputs, l, "Expected a guard event"))?;
+                Ok((time, r))
             })
-            .map(|(t, r)| {
+            .map(|line: Result<_, AoCError>| {
+                let (t, r) = line?;
                 let state = match r.trim() {
                     "wakes up" => GuardState::Awake,
                     "falls asleep" => GuardState::Asleep,
                     new_shift => {
-                        last_id = new_shift[7..]
-                            .trim_end_matches(" begins shift")
+                        let id = new_shift
+                            .trim_start_matches("Guard #")
+                            .trim_end_matches(" begins shift");
+                        last_id = id
                             .parse::<usize>()
-                            .unwrap();
+                            .map_err(|_| AoCError::parse_at(inputs, id, "Expected a guard id"))?;
                         GuardState::Awake
                     }
                 };
-                (t, last_id, state)
+                Ok((t, last_id, state))
             })
             .collect()
     }
 
-    fn calculate_schedule(inputs: &str) -> HashMap<usize, ([usize; 60], usize)> {
-        let mut sleep_schedule: HashMap<usize, ([usize; 60], usize)> = HashMap::new();
+    fn calculate_schedule(inputs: &str) -> Result<Schedule, AoCError> {
+        let mut sleep_schedule: Schedule = HashMap::new();
 
-        let inputs = Self::parse_inputs(inputs);
+        let inputs = Self::parse_inputs(inputs)?;
 
         let minute = Duration::minutes(1);
         let mut iter = inputs.iter();
-        let mut last: &(NaiveDateTime, usize, GuardState) = iter.next().unwrap().clone();
+        let mut last: &(NaiveDateTime, usize, GuardState) = iter
+            .next()
+            .ok_or_else(|| AoCError::invalid_input("No guard records"))?;
         for this in iter {
             let (time, id, _) = this;
             let (last_time, last_id, last_state) = last;
@@ -61,7 +69,9 @@ impl Day04 {
                 if id != last_id {
                     let mut tick = last_time.time();
                     if tick.hour() != 0 {
-                        panic!("Guard slept through his entire shift");
+                        return Err(AoCError::invalid_input(
+                            "Guard slept through his entire shift",
+                        ));
                     }
                     while tick < NaiveTime::from_hms(0, 59, 0) {
                         let (schedule, sum) =
@@ -71,9 +81,11 @@ impl Day04 {
                         tick += minute;
                     }
                 } else {
-                    let mut tick = last_time.clone();
+                    let mut tick = *last_time;
                     if tick.hour() != 0 {
-                        panic!("Guard is asleep before he even started...");
+                        return Err(AoCError::invalid_input(
+                            "Guard is asleep before he even started...",
+                        ));
                     }
                     while tick < *time {
                         let (schedule, sum) = sleep_schedule.entry(*id).or_insert(([0; 60], 0));
@@ -86,14 +98,18 @@ impl Day04 {
             last = this;
         }
 
-        sleep_schedule
+        Ok(sleep_schedule)
     }
 }
 
 impl AoC<usize, usize> for Day04 {
-    fn task_a(inputs: &str) -> Result<usize, Box<Error>> {
-        let sleep_schedule = Day04::calculate_schedule(inputs);
+    type Input = Schedule;
+
+    fn parse(inputs: &str) -> Result<Schedule, AoCError> {
+        Self::calculate_schedule(inputs)
+    }
 
+    fn solve_a(sleep_schedule: &Schedule) -> Result<usize, AoCError> {
         let (sleepiest_guard, _) = sleep_schedule
             .par_iter()
             .map(|(guard, (_, sum))| (guard, sum))
@@ -116,10 +132,12 @@ impl AoC<usize, usize> for Day04 {
 
         let mut sleepiest_minute: usize = 0;
         let mut sleepiest_minute_i: usize = 0;
-        let (schedule, _) = sleep_schedule.get(&sleepiest_guard).unwrap();
-        for i in 0..60 {
-            if schedule[i] > sleepiest_minute {
-                sleepiest_minute = schedule[i];
+        let (schedule, _) = sleep_schedule
+            .get(sleepiest_guard)
+            .ok_or_else(|| AoCError::no_solution("No guard ever fell asleep"))?;
+        for (i, &minute) in schedule.iter().enumerate() {
+            if minute > sleepiest_minute {
+                sleepiest_minute = minute;
                 sleepiest_minute_i = i;
             }
         }
@@ -127,9 +145,7 @@ impl AoC<usize, usize> for Day04 {
         Ok(sleepiest_guard * sleepiest_minute_i)
     }
 
-    fn task_b(inputs: &str) -> Result<usize, Box<Error>> {
-        let sleep_schedule = Day04::calculate_schedule(inputs);
-
+    fn solve_b(sleep_schedule: &Schedule) -> Result<usize, AoCError> {
         let (guard, minute_index, _) = sleep_schedule
             .iter()
             .map(|(guard, (schedule, _))| (guard, schedule))
@@ -145,10 +161,14 @@ impl AoC<usize, usize> for Day04 {
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day04, _, _>(2018, 4, "Repose Record");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::Day04;
     use aoc_base::AoC;
 
@@ -170,6 +190,11 @@ mod tests {
                              [1518-11-01 00:30] falls asleep          \n\
                              [1518-11-04 00:46] wakes up              ";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         assert_eq!(Day04::task_a(TEST_DATA).unwrap(), 240);
@@ -180,13 +205,15 @@ mod tests {
         assert_eq!(Day04::task_b(TEST_DATA).unwrap(), 4455);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
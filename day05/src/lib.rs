@@ -1,9 +1,6 @@
-#![feature(test)]
-
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use rayon::prelude::*;
 use std::collections::HashSet;
-use std::error::Error;
 use std::iter::repeat;
 
 pub struct Day05;
@@ -20,14 +17,14 @@ impl Day05 {
                 return true;
             };
         }
-        return false;
+        false
     }
 
     fn keep_char(a: &char, b: &char) -> bool {
         if a != b {
             return !Self::same_letter(a, b);
         }
-        return true;
+        true
     }
 
     fn react_polymer<T>(input: T) -> usize
@@ -50,16 +47,24 @@ impl Day05 {
 }
 
 impl AoC<usize, usize> for Day05 {
-    fn task_a(inputs: &str) -> Result<usize, Box<Error>> {
-        let input = inputs.lines().next().unwrap();
-        Ok(Self::react_polymer(input.chars()))
+    type Input = Vec<char>;
+
+    fn parse(inputs: &str) -> Result<Vec<char>, AoCError> {
+        let polymer = inputs
+            .lines()
+            .next()
+            .ok_or_else(|| AoCError::invalid_input("No polymer in input"))?;
+        Ok(polymer.chars().collect())
+    }
+
+    fn solve_a(polymer: &Vec<char>) -> Result<usize, AoCError> {
+        Ok(Self::react_polymer(polymer.iter().cloned()))
     }
 
-    fn task_b(inputs: &str) -> Result<usize, Box<Error>> {
-        let polymer: Vec<char> = inputs.lines().next().unwrap().chars().collect();
+    fn solve_b(polymer: &Vec<char>) -> Result<usize, AoCError> {
         let polymer_iter = repeat(polymer.clone());
         let mut all_units: HashSet<char> = HashSet::with_capacity(25);
-        for c in polymer {
+        for &c in polymer {
             if let Some(cl) = c.to_lowercase().next() {
                 all_units.insert(cl);
             }
@@ -70,19 +75,23 @@ impl AoC<usize, usize> for Day05 {
             .par_iter()
             .map(|(u, p)| repeat(*u).zip(p))
             .map(|p| {
-                p.filter(|(u, c)| !Self::same_letter(c, &u))
+                p.filter(|(u, c)| !Self::same_letter(c, u))
                     .map(|(_, c)| *c)
             })
             .map(|p| Self::react_polymer(p))
-            .reduce(|| std::usize::MAX, std::cmp::min);
+            .reduce(|| usize::MAX, std::cmp::min);
         Ok(shortest)
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day05, _, _>(2018, 5, "Alchemical Reduction");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::Day05;
     use aoc_base::AoC;
 
@@ -94,6 +103,11 @@ mod tests {
         ("dabAcCaCBAcCcaDA", 10),
     ];
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         for (input, result) in TEST_DATA_A {
@@ -109,13 +123,15 @@ mod tests {
         assert_eq!(Day05::task_b(input).unwrap(), result);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
@@ -1,18 +1,23 @@
-#![feature(test)]
-
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Params, Registry};
 use rayon::prelude::*;
 use std::collections::HashMap;
-use std::error::Error;
 use std::iter::repeat;
 
 pub struct Day06;
 
-fn parse_inputs(inputs: &str) -> Result<Vec<(i32, i32)>, Box<Error>> {
+fn parse_inputs(inputs: &str) -> Result<Vec<(i32, i32)>, AoCError> {
     inputs
         .lines()
-        .map(|s| s.split(", ").map(|c| c.parse()).collect())
-        .map(|s: Vec<Result<i32, _>>| Ok((s[0].clone()?, s[1].clone()?)))
+        .map(|l| {
+            let mut coords = l.split(", ").map(|c| {
+                c.parse::<i32>()
+                    .map_err(|e| AoCError::parse_at(inputs, c, e.to_string()))
+            });
+            match (coords.next(), coords.next(), coords.next()) {
+                (Some(x), Some(y), None) => Ok((x?, y?)),
+                _ => Err(AoCError::parse_at(inputs, l, "Expected a coordinate pair")),
+            }
+        })
         .collect()
 }
 
@@ -20,10 +25,10 @@ fn get_dimensions<'a, T>(coords: T) -> (i32, i32, i32, i32)
 where
     T: IntoIterator<Item = &'a (i32, i32)> + 'a,
 {
-    let mut min_x = std::i32::MAX;
-    let mut min_y = std::i32::MAX;
-    let mut max_x = std::i32::MIN;
-    let mut max_y = std::i32::MIN;
+    let mut min_x = i32::MAX;
+    let mut min_y = i32::MAX;
+    let mut max_x = i32::MIN;
+    let mut max_y = i32::MIN;
     for (x, y) in coords.into_iter() {
         min_x = i32::min(*x, min_x);
         min_y = i32::min(*y, min_y);
@@ -35,7 +40,7 @@ where
 }
 
 fn distance((x1, y1): &(i32, i32), (x2, y2): &(i32, i32)) -> usize {
-    return ((x2 - x1).abs() + (y2 - y1).abs()) as usize;
+    ((x2 - x1).abs() + (y2 - y1).abs()) as usize
 }
 
 fn all_coords(dimensions: (i32, i32, i32, i32)) -> Vec<(i32, i32)> {
@@ -46,9 +51,14 @@ fn all_coords(dimensions: (i32, i32, i32, i32)) -> Vec<(i32, i32)> {
 }
 
 impl AoC<usize, usize> for Day06 {
-    fn task_a(inputs: &str) -> Result<usize, Box<Error>> {
-        let coords: Vec<(i32, i32)> = parse_inputs(inputs)?;
-        let (min_x, min_y, max_x, max_y) = get_dimensions(&coords);
+    type Input = Vec<(i32, i32)>;
+
+    fn parse(inputs: &str) -> Result<Vec<(i32, i32)>, AoCError> {
+        parse_inputs(inputs)
+    }
+
+    fn solve_a(coords: &Vec<(i32, i32)>) -> Result<usize, AoCError> {
+        let (min_x, min_y, max_x, max_y) = get_dimensions(coords);
 
         let all_coords = all_coords((min_x, min_y, max_x, max_y));
         let all_coords_iter = rayon::iter::repeat(all_coords.clone());
@@ -56,7 +66,7 @@ impl AoC<usize, usize> for Day06 {
         let all_coords_empty = || {
             all_coords
                 .par_iter()
-                .map(|p| (p.clone(), None, std::usize::MAX))
+                .map(|p| (*p, None, usize::MAX))
                 .collect::<Vec<_>>()
         };
 
@@ -64,7 +74,7 @@ impl AoC<usize, usize> for Day06 {
             .zip(coords)
             .map(|(ac, sp)| {
                 ac.par_iter()
-                    .map(|p| (p.clone(), Some(sp.clone()), distance(p, &sp)))
+                    .map(|p| (*p, Some(*sp), distance(p, sp)))
                     .collect::<Vec<_>>()
             })
             .reduce(all_coords_empty, |v1, v2| {
@@ -103,29 +113,43 @@ impl AoC<usize, usize> for Day06 {
             .fold(0, |s1, (_, s2)| std::cmp::max(s1, *s2)))
     }
 
-    fn task_b(inputs: &str) -> Result<usize, Box<Error>> {
-        let coords: Vec<(i32, i32)> = parse_inputs(inputs)?;
-        let all_coords = all_coords(get_dimensions(&coords));
+    fn solve_b(coords: &Vec<(i32, i32)>) -> Result<usize, AoCError> {
+        Self::solve_b_with(coords, &Params::new())
+    }
+
+    /// The example looks for a total distance below 32 instead of 10000
+    fn solve_b_with(coords: &Vec<(i32, i32)>, params: &Params) -> Result<usize, AoCError> {
+        let max_distance: usize = params.get("max_distance", 10000)?;
+        let all_coords = all_coords(get_dimensions(coords));
 
         let region_size = all_coords
             .par_iter()
             .map(|p| coords.par_iter().map(|sp| distance(p, sp)).sum::<usize>())
-            .filter(|&td| td < 10000)
+            .filter(|&td| td < max_distance)
             .count();
 
         Ok(region_size)
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day06, _, _>(2018, 6, "Chronal Coordinates");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::Day06;
     use aoc_base::AoC;
 
     const TEST_DATA: &str = "1, 1\n1, 6\n8, 3\n3, 4\n5, 5\n8, 9";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         assert_eq!(Day06::task_a(TEST_DATA).unwrap(), 17);
@@ -138,13 +162,15 @@ mod tests {
         assert_eq!(Day06::task_b(TEST_DATA).unwrap(), 72 /* should be 16 */);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
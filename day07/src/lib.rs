@@ -1,23 +1,31 @@
-#![feature(test)]
-
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Params, Registry};
 use binary_heap_plus::BinaryHeap;
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
-use std::error::Error;
 
 pub struct Day07;
 
-fn get_nodes_with_dependencies(input: &str) -> (HashSet<char>, HashMap<char, HashSet<char>>) {
-    let input: Vec<&str> = input.lines().collect();
+/// All steps, and the steps each of them depends on
+type Graph = (HashSet<char>, HashMap<char, HashSet<char>>);
+
+fn parse_step(input: &str, line: &str) -> Result<(char, char), AoCError> {
+    let mut cs = line.chars();
+    match (cs.nth(5), cs.nth(30)) {
+        (Some(fst), Some(snd)) if fst.is_ascii_uppercase() && snd.is_ascii_uppercase() => {
+            Ok((snd, fst))
+        }
+        _ => Err(AoCError::parse_at(input, line, "Expected a step dependency")),
+    }
+}
+
+fn get_nodes_with_dependencies(input: &str) -> Result<Graph, AoCError> {
+    let steps: Vec<(char, char)> = input
+        .lines()
+        .map(|l| parse_step(input, l))
+        .collect::<Result<_, _>>()?;
     let fold_id = || (HashSet::new(), HashMap::new());
-    input
+    Ok(steps
         .into_par_iter()
-        .map(|l| {
-            let mut cs = l.chars();
-            let (fst, snd) = (cs.nth(5).unwrap(), cs.nth(30).unwrap());
-            (snd, fst)
-        })
         .fold(fold_id, |(mut nodes, mut deps), (snd, fst)| {
             nodes.insert(fst);
             nodes.insert(snd);
@@ -30,17 +38,16 @@ fn get_nodes_with_dependencies(input: &str) -> (HashSet<char>, HashMap<char, Has
                 dps1.entry(*k).or_insert(HashSet::new()).extend(v);
             }
             (nds1, dps1)
-        })
+        }))
 }
 
 fn work_completion_time(
-    input: &str,
+    (all_nodes, dependencies): &Graph,
     worker_count: usize,
     base_time: usize,
-) -> Result<usize, Box<Error>> {
+) -> Result<usize, AoCError> {
     let mut workers: HashMap<char, usize> = HashMap::new();
-
-    let (all_nodes, mut dependencies) = get_nodes_with_dependencies(input);
+    let mut dependencies = dependencies.clone();
 
     let mut pqueue = BinaryHeap::new_min();
     all_nodes
@@ -58,7 +65,7 @@ fn work_completion_time(
                 break;
             }
         }
-        if workers.len() == 0 {
+        if workers.is_empty() {
             break;
         };
 
@@ -87,8 +94,14 @@ fn work_completion_time(
 }
 
 impl AoC<String, usize> for Day07 {
-    fn task_a(input: &str) -> Result<String, Box<Error>> {
-        let (all_nodes, mut dependencies) = get_nodes_with_dependencies(input);
+    type Input = Graph;
+
+    fn parse(input: &str) -> Result<Graph, AoCError> {
+        get_nodes_with_dependencies(input)
+    }
+
+    fn solve_a((all_nodes, dependencies): &Graph) -> Result<String, AoCError> {
+        let mut dependencies = dependencies.clone();
         let mut pqueue = BinaryHeap::new_min();
         all_nodes
             .iter()
@@ -105,18 +118,34 @@ impl AoC<String, usize> for Day07 {
             result.push(n);
         }
 
+        if result.len() < all_nodes.len() {
+            return Err(AoCError::no_solution("The steps have cyclic dependencies"));
+        }
         Ok(result.iter().collect())
     }
 
-    fn task_b(inputs: &str) -> Result<usize, Box<Error>> {
-        work_completion_time(inputs, 5, 60)
+    fn solve_b(graph: &Graph) -> Result<usize, AoCError> {
+        Self::solve_b_with(graph, &Params::new())
+    }
+
+    /// The example has 2 workers and no base time, instead of 5 and 60
+    fn solve_b_with(graph: &Graph, params: &Params) -> Result<usize, AoCError> {
+        work_completion_time(
+            graph,
+            params.get("workers", 5)?,
+            params.get("base_time", 60)?,
+        )
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day07, _, _>(2018, 7, "The Sum of Its Parts");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::*;
     use aoc_base::AoC;
 
@@ -128,6 +157,11 @@ mod tests {
                              Step D must be finished before step E can begin.\n\
                              Step F must be finished before step E can begin.";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         assert_eq!(Day07::task_a(TEST_DATA).unwrap(), "CABDFE");
@@ -135,16 +169,19 @@ mod tests {
 
     #[test]
     fn test_b() {
-        assert_eq!(work_completion_time(TEST_DATA, 2, 0).unwrap(), 15);
+        let graph = Day07::parse(TEST_DATA).unwrap();
+        assert_eq!(work_completion_time(&graph, 2, 0).unwrap(), 15);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
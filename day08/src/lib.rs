@@ -1,48 +1,58 @@
-#![feature(test)]
-#![feature(try_trait)]
-#![feature(uniform_paths)]
 mod tree;
 
-use aoc_base::AoC;
-use std::error::Error;
+use aoc_base::{AoC, AoCError, Registry};
 use tree::TreeNode;
 
 pub struct Day08;
 
 impl AoC<usize, usize> for Day08 {
-    fn task_a(input: &str) -> Result<usize, Box<Error>> {
-        let tree: TreeNode = input.parse()?;
+    type Input = TreeNode;
+
+    fn parse(input: &str) -> Result<TreeNode, AoCError> {
+        input.parse()
+    }
+
+    fn solve_a(tree: &TreeNode) -> Result<usize, AoCError> {
         Ok(tree.iter()
             .map(|m| m.iter().sum::<usize>())
             .sum())
     }
 
-    fn task_b(input: &str) -> Result<usize, Box<Error>> {
+    fn solve_b(tree: &TreeNode) -> Result<usize, AoCError> {
         fn rec_sum(node: &TreeNode) -> usize {
-            if node.children.len() == 0 {
+            if node.children.is_empty() {
                 node.metadata.iter().sum()
             } else {
                 node.metadata.iter()
                     .filter(|&&m| m != 0)
                     .map(|m| m - 1)
                     .filter_map(|m| node.children.get(m))
-                    .map(|c| rec_sum(c))
+                    .map(rec_sum)
                     .sum()
             }
         }
-        Ok(rec_sum(&input.parse()?))
+        Ok(rec_sum(tree))
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day08, _, _>(2018, 8, "Memory Maneuver");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::Day08;
     use aoc_base::AoC;
 
     const TEST_DATA: &str = "2 3 0 3 10 11 12 1 1 0 1 99 2 1 1 2";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         assert_eq!(Day08::task_a(TEST_DATA).unwrap(), 138);
@@ -53,13 +63,15 @@ mod tests {
         assert_eq!(Day08::task_b(TEST_DATA).unwrap(), 66);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
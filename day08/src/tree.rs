@@ -1,57 +1,33 @@
+use aoc_base::AoCError;
 use std::str::FromStr;
-use std::error::Error;
-use std::fmt::{Display, Formatter, self};
-use std::option::NoneError;
-use std::num::ParseIntError;
 
 pub struct TreeNode {
     pub children: Vec<TreeNode>,
     pub metadata: Vec<usize>,
 }
 
-
-#[derive(Debug)]
-pub enum TreeParseError {
-    UnexpectedEndOfInput,
-    ParseError(ParseIntError),
-}
-impl Error for TreeParseError {}
-
-impl From<NoneError> for TreeParseError {
-    fn from(_: NoneError) -> TreeParseError {
-        TreeParseError::UnexpectedEndOfInput
-    }
-}
-
-impl From<ParseIntError> for TreeParseError {
-    fn from(e: ParseIntError) -> TreeParseError {
-        TreeParseError::ParseError(e)
-    }
-}
-
-impl Display for TreeParseError {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "{}", match self {
-            TreeParseError::UnexpectedEndOfInput => "Unexpected end of input".into(),
-            TreeParseError::ParseError(e) => format!("Could not parse data: {}", e),
-        })
-    }
+fn next_number<'a, I>(input: &str, iter: &mut I) -> Result<usize, AoCError>
+    where I: Iterator<Item=&'a str>,
+{
+    let s = iter.next()
+        .ok_or_else(|| AoCError::invalid_input("Unexpected end of input"))?;
+    s.parse()
+        .map_err(|e| AoCError::parse_at(input, s, format!("Could not parse data: {}", e)))
 }
 
-
 impl TreeNode {
-    fn rec_parse<'a, 'b, I>(iter: &'a mut I) -> Result<TreeNode, TreeParseError>
+    fn rec_parse<'a, 'b, I>(input: &'b str, iter: &'a mut I) -> Result<TreeNode, AoCError>
         where I: Iterator<Item=&'b str>,
               'b: 'a,
     {
-        let child_count: usize = iter.next()?.parse()?;
-        let meta_count: usize = iter.next()?.parse()?;
+        let child_count: usize = next_number(input, iter)?;
+        let meta_count: usize = next_number(input, iter)?;
         let children: Vec<TreeNode> = (0..child_count)
-            .map(|_| Self::rec_parse(iter))
-            .collect::<Result<_, TreeParseError>>()?;
+            .map(|_| Self::rec_parse(input, iter))
+            .collect::<Result<_, AoCError>>()?;
         let metadata: Vec<usize> = (0..meta_count)
-            .map(|_| iter.next().map(|s| s.parse()))
-            .collect::<Option<Result<_, ParseIntError>>>()??;
+            .map(|_| next_number(input, iter))
+            .collect::<Result<_, AoCError>>()?;
 
         Ok(TreeNode {
             children,
@@ -59,9 +35,9 @@ impl TreeNode {
         })
     }
 
-    pub fn iter(&self) -> TreeIter {
+    pub fn iter(&self) -> TreeIter<'_> {
         TreeIter {
-            node: &self,
+            node: self,
             probe: None,
             state: 0,
         }
@@ -69,10 +45,10 @@ impl TreeNode {
 }
 
 impl FromStr for TreeNode {
-    type Err = TreeParseError;
+    type Err = AoCError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::rec_parse(&mut s.trim().split(' '))
+        Self::rec_parse(s, &mut s.trim().split(' '))
     }
 }
 
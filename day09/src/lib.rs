@@ -1,13 +1,10 @@
-#![feature(test)]
-
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use std::collections::VecDeque;
-use std::error::Error;
 
 pub struct Day09;
 
 fn rotate(queue: &mut VecDeque<usize>, n: i32) {
-    if queue.len() == 0 {
+    if queue.is_empty() {
         return;
     }
     if n > 0 {
@@ -23,10 +20,19 @@ fn rotate(queue: &mut VecDeque<usize>, n: i32) {
     }
 }
 
-fn parse_input(input: &str) -> Result<(usize, usize), Box<Error>> {
+fn parse_input(input: &str) -> Result<(usize, usize), AoCError> {
+    let parse_count = |s: Option<&str>, what: &str| -> Result<usize, AoCError> {
+        let s = s.ok_or_else(|| AoCError::invalid_input(format!("Missing {}", what)))?;
+        s.parse::<usize>()
+            .map_err(|e| AoCError::parse_at(input, s, format!("Invalid {}: {}", what, e)))
+    };
+
     let mut input_iter = input.split(" ");
-    let player_count = input_iter.next().unwrap().parse::<usize>()?;
-    let marble_count = input_iter.nth(5).unwrap().parse::<usize>()?;
+    let player_count = parse_count(input_iter.next(), "player count")?;
+    let marble_count = parse_count(input_iter.nth(5), "marble count")?;
+    if player_count == 0 {
+        return Err(AoCError::invalid_input("There must be at least one player"));
+    }
     Ok((player_count, marble_count))
 }
 
@@ -56,21 +62,29 @@ fn marble_game(player_count: usize, marble_count: usize) -> usize {
 }
 
 impl AoC<usize, usize> for Day09 {
-    fn task_a(input: &str) -> Result<usize, Box<Error>> {
-        let (player_count, marble_count) = parse_input(input)?;
+    type Input = (usize, usize);
+
+    fn parse(input: &str) -> Result<(usize, usize), AoCError> {
+        parse_input(input)
+    }
+
+    fn solve_a(&(player_count, marble_count): &(usize, usize)) -> Result<usize, AoCError> {
         Ok(marble_game(player_count, marble_count))
     }
 
-    fn task_b(input: &str) -> Result<usize, Box<Error>> {
-        let (player_count, marble_count) = parse_input(input)?;
+    fn solve_b(&(player_count, marble_count): &(usize, usize)) -> Result<usize, AoCError> {
         Ok(marble_game(player_count, marble_count * 100))
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day09, _, _>(2018, 9, "Marble Mania");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::Day09;
     use aoc_base::AoC;
 
@@ -103,6 +117,11 @@ mod tests {
         ),
     ];
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_a() {
         for (case, result_a, _) in TEST_DATA {
@@ -117,13 +136,15 @@ mod tests {
         }
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    #[bench]
-    fn bench_b(b: &mut Bencher) {
-        b.iter(test_b)
+    #[test]
+    #[ignore]
+    fn bench_b() {
+        bench("bench_b", |b| b.iter(test_b));
     }
 }
@@ -1,7 +1,4 @@
-#![feature(test)]
-
-use aoc_base::AoC;
-use std::error::Error;
+use aoc_base::{AoC, AoCError, Params, Registry};
 use std::collections::HashSet;
 
 pub struct Day10;
@@ -10,28 +7,44 @@ type Vec2 = (i32, i32);
 type Bounds = (i32, i32, i32, i32);
 type Points = Vec<(Vec2, Vec2)>;
 
-fn parse_input(input: &str) -> Points {
-    input
+fn parse_vec2(input: &str, vec: &str) -> Result<Vec2, AoCError> {
+    let mut ns = vec.split(",")
+        .map(|n| {
+            let n = n.trim();
+            n.parse::<i32>().map_err(|e| AoCError::parse_at(input, n, e.to_string()))
+        });
+    match (ns.next(), ns.next(), ns.next()) {
+        (Some(x), Some(y), None) => Ok((x?, y?)),
+        _ => Err(AoCError::parse_at(input, vec, "Expected a vector")),
+    }
+}
+
+fn parse_input(input: &str) -> Result<Points, AoCError> {
+    let points = input
         .lines()
         .map(|l| {
             let mut vals = l.trim_start_matches("position=<")
             .trim_end_matches(">")
             .split("> velocity=<")
-            .map(|vec| {
-                 let mut ns = vec.split(",")
-                    .map(|n| n.trim().parse::<i32>().unwrap());
-                 (ns.next().unwrap(), ns.next().unwrap())
-            });
-            (vals.next().unwrap(), vals.next().unwrap())
+            .map(|vec| parse_vec2(input, vec));
+            match (vals.next(), vals.next(), vals.next()) {
+                (Some(p), Some(v), None) => Ok((p?, v?)),
+                _ => Err(AoCError::parse_at(input, l, "Expected a position and a velocity")),
+            }
         })
-        .collect()
+        .collect::<Result<Points, AoCError>>()?;
+
+    if points.is_empty() {
+        return Err(AoCError::invalid_input("No points in input"));
+    }
+    Ok(points)
 }
 
 fn calc_bounds(points: &Points) -> Bounds {
-    let mut min_x = std::i32::MAX;
-    let mut min_y = std::i32::MAX;
-    let mut max_x = std::i32::MIN;
-    let mut max_y = std::i32::MIN;
+    let mut min_x = i32::MAX;
+    let mut min_y = i32::MAX;
+    let mut max_x = i32::MIN;
+    let mut max_y = i32::MIN;
 
     for ((x, y), _) in points.iter() {
         if *x < min_x { min_x = *x; }
@@ -44,7 +57,7 @@ fn calc_bounds(points: &Points) -> Bounds {
 
 fn out_of_bounds(points: &Points, original: &Bounds) -> bool {
     let (minx, miny, maxx, maxy) = original;
-    let (minxp, minyp, maxxp, maxyp) = calc_bounds(&points);
+    let (minxp, minyp, maxxp, maxyp) = calc_bounds(points);
     minxp < *minx ||
     minyp < *miny ||
     maxxp > *maxx ||
@@ -57,7 +70,7 @@ fn draw_points(points: &Points) -> String {
     let mut drawn: Vec<char> = vec![];
     for y in min_y..=max_y {
         for x in min_x..=max_x {
-            if map.get(&(x, y)).is_some() {
+            if map.contains(&(x, y)) {
                 drawn.push('#');
             } else {
                 drawn.push('.');
@@ -84,9 +97,8 @@ fn scatter((_, min_y, _, max_y): Bounds) -> i32 {
     (max_y - min_y).abs()
 }
 
-fn solve_constellation(input: &str, font_height: i32) -> (String, i32) {
-    let points = parse_input(input);
-    let bounds = calc_bounds(&points);
+fn solve_constellation(points: &Points, font_height: i32) -> (String, i32) {
+    let bounds = calc_bounds(points);
     let init_scatter = scatter(bounds);
 
     let mut step_size: i32 = 2;
@@ -95,7 +107,7 @@ fn solve_constellation(input: &str, font_height: i32) -> (String, i32) {
     }
 
     let mut seconds = 0;
-    let mut state: Points = points;
+    let mut state: Points = points.clone();
     while !out_of_bounds(&state, &bounds) {
         let curr_scatter = scatter(calc_bounds(&state));
         if curr_scatter == font_height {
@@ -120,21 +132,40 @@ fn solve_constellation(input: &str, font_height: i32) -> (String, i32) {
 }
 
 impl AoC<String, i32> for Day10 {
-    fn task_a(input: &str) -> Result<String, Box<Error>> {
-        let (rendered, _) = solve_constellation(input, 9);
+    type Input = Points;
+
+    fn parse(input: &str) -> Result<Points, AoCError> {
+        parse_input(input)
+    }
+
+    fn solve_a(points: &Points) -> Result<String, AoCError> {
+        Self::solve_a_with(points, &Params::new())
+    }
+
+    fn solve_b(points: &Points) -> Result<i32, AoCError> {
+        Self::solve_b_with(points, &Params::new())
+    }
+
+    /// The letters of the example are shorter than those of the real input
+    fn solve_a_with(points: &Points, params: &Params) -> Result<String, AoCError> {
+        let (rendered, _) = solve_constellation(points, params.get("font_height", 9)?);
         Ok(rendered)
     }
 
-    fn task_b(input: &str) -> Result<i32, Box<Error>> {
-        let (_, seconds) = solve_constellation(input, 9);
+    fn solve_b_with(points: &Points, params: &Params) -> Result<i32, AoCError> {
+        let (_, seconds) = solve_constellation(points, params.get("font_height", 9)?);
         Ok(seconds)
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day10, _, _>(2018, 10, "The Stars Align");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::*;
 
     const TEST_DATA: &str = "position=< 9,  1> velocity=< 0,  2>\n\
@@ -169,10 +200,15 @@ mod tests {
                              position=<14,  7> velocity=<-2,  0>\n\
                              position=<-3,  6> velocity=< 2, -1>";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test() {
         assert_eq!(
-            solve_constellation(TEST_DATA, 7),
+            solve_constellation(&parse_input(TEST_DATA).unwrap(), 7),
             (
                 "#...#..###\n\
                 #...#...#.\n\
@@ -186,8 +222,9 @@ mod tests {
             ));
     }
 
-    #[bench]
-    fn bench(b: &mut Bencher) {
-        b.iter(test)
+    #[test]
+    #[ignore]
+    fn bench_test() {
+        bench("bench_test", |b| b.iter(test));
     }
 }
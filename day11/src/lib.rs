@@ -1,7 +1,4 @@
-#![feature(test)]
-
-use aoc_base::AoC;
-use std::error::Error;
+use aoc_base::{AoC, AoCError, Registry};
 use rayon::iter::repeat;
 use std::collections::HashMap;
 use rayon::prelude::*;
@@ -69,7 +66,7 @@ impl Day11 {
         Self::get_squares(3, &cells)
             .map(|(p, _, v)| (p, v))
             //.fold(((0, 0), std::i32::MIN), |(p1, v1), (p2, v2)| {
-            .reduce(|| (Pos(0, 0), std::i32::MIN), |(p1, v1), (p2, v2)| {
+            .reduce(|| (Pos(0, 0), i32::MIN), |(p1, v1), (p2, v2)| {
                 if v1 > v2 {(p1, v1)} else {(p2, v2)}
             })
     }
@@ -79,30 +76,47 @@ impl Day11 {
 
         size_range.into_par_iter()
             .flat_map(|s| Self::get_squares(s, &cells))
-            .reduce(|| (Pos(0, 0), 0, std::i32::MIN), |(p1, s1, v1), (p2, s2, v2)| {
+            .reduce(|| (Pos(0, 0), 0, i32::MIN), |(p1, s1, v1), (p2, s2, v2)| {
                 if v1 > v2 {(p1, s1, v1)} else {(p2, s2, v2)}
             })
     }
 }
 
 impl AoC<Pos, String> for Day11 {
-    fn task_a(input: &str) -> Result<Pos, Box<Error>> {
-        Ok(Self::largest_power_level(input.trim().parse()?, 3..4).0)
+    type Input = i32;
+
+    fn parse(input: &str) -> Result<i32, AoCError> {
+        let s = input.trim();
+        s.parse()
+            .map_err(|e| AoCError::parse_at(input, s, format!("Invalid serial number: {}", e)))
     }
 
-    fn task_b(input: &str) -> Result<String, Box<Error>> {
-        let (p, s, _) = Self::largest_power_level(input.trim().parse()?, 1..301);
+    fn solve_a(&serial_number: &i32) -> Result<Pos, AoCError> {
+        Ok(Self::largest_power_level(serial_number, 3..4).0)
+    }
+
+    fn solve_b(&serial_number: &i32) -> Result<String, AoCError> {
+        let (p, s, _) = Self::largest_power_level(serial_number, 1..301);
         Ok(format!("{}@{}²", p, s))
     }
 }
 
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day11, _, _>(2018, 11, "Chronal Charge");
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::*;
 
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_power_level() {
         let cases = vec![
@@ -129,13 +143,15 @@ mod tests {
         assert_eq!(Day11::largest_power_level(42, 1..301), (Pos(232, 251), 12, 119));
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 
-    //#[bench]
-    //fn bench_b(b: &mut Bencher) {
-    //    b.iter(test_b)
+    //#[test]
+    //#[ignore]
+    //fn bench_b() {
+    //    bench("bench_b", |b| b.iter(test_b));
     //}
 }
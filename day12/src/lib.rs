@@ -1,18 +1,18 @@
-#![feature(test)]
-
-use aoc_base::AoC;
+use aoc_base::{AoC, AoCError, Registry};
 use std::collections::HashSet;
-use std::error::Error;
 
 pub struct Day12;
 
+/// The initial pots with plants, and the patterns which produce a plant
+type Rules = (HashSet<i64>, HashSet<String>);
+
 impl Day12 {
-    fn parse_input(input: &str) -> (HashSet<i64>, HashSet<String>) {
+    fn parse_input(input: &str) -> Result<Rules, AoCError> {
         let mut lines = input.lines();
 
         let state: HashSet<i64> = lines
             .next()
-            .unwrap()
+            .ok_or_else(|| AoCError::invalid_input("Missing initial state"))?
             .trim_start_matches("initial state: ")
             .trim()
             .chars()
@@ -26,17 +26,29 @@ impl Day12 {
         let valid_combos: HashSet<String> = lines
             .skip(1)
             .map(|l| l.trim())
-            .filter(|l| l.chars().last().unwrap() == '#')
-            .map(|l| l.chars().take(5).collect())
-            .collect();
+            .map(|l| {
+                let mut parts = l.split(" => ");
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(combo), Some(result), None) if combo.chars().count() == 5 => {
+                        Ok((combo, result))
+                    }
+                    _ => Err(AoCError::parse_at(input, l, "Expected a rule")),
+                }
+            })
+            .filter(|rule| match rule {
+                Ok((_, result)) => *result == "#",
+                Err(_) => true,
+            })
+            .map(|rule| rule.map(|(combo, _)| combo.to_owned()))
+            .collect::<Result<_, _>>()?;
 
-        (state, valid_combos)
+        Ok((state, valid_combos))
     }
 
     fn pattern_equals(a: &HashSet<i64>, b: &HashSet<i64>) -> Option<i64> {
         if a.len() != b.len() {
             None
-        } else if a.len() == 0 {
+        } else if a.is_empty() {
             Some(0)
         } else {
             let amin = a.iter().min().unwrap();
@@ -57,9 +69,11 @@ impl Day12 {
         (min - 2, max + 2)
     }
 
-    fn solve(input: &str, iterations: i64) -> i64 {
-        let (mut state, valid_combos) = Self::parse_input(input);
+    fn solve((initial_state, valid_combos): &Rules, iterations: i64) -> Result<i64, AoCError> {
+        let mut state = initial_state.clone();
         for i in 1..=iterations {
+            // Without a stable pattern, part B would take all of its iterations
+            aoc_base::check_cancelled()?;
             let mut new_state = HashSet::new();
             let (min, max) = Self::get_plant_range(&state);
             for j in min..=max {
@@ -79,29 +93,39 @@ impl Day12 {
                 let sum: i64 = new_state.iter().sum();
                 let iter_left = iterations - i;
                 let step = d * state.len() as i64;
-                return sum + step * iter_left;
+                return Ok(sum + step * iter_left);
             }
 
             std::mem::swap(&mut state, &mut new_state);
         }
-        state.iter().sum()
+        Ok(state.iter().sum())
     }
 }
 
 impl AoC<i64, i64> for Day12 {
-    fn task_a(input: &str) -> Result<i64, Box<Error>> {
-        Ok(Self::solve(input, 20))
+    type Input = Rules;
+
+    fn parse(input: &str) -> Result<Rules, AoCError> {
+        Self::parse_input(input)
     }
 
-    fn task_b(input: &str) -> Result<i64, Box<Error>> {
-        Ok(Self::solve(input, 50000000000))
+    fn solve_a(rules: &Rules) -> Result<i64, AoCError> {
+        Self::solve(rules, 20)
     }
+
+    fn solve_b(rules: &Rules) -> Result<i64, AoCError> {
+        Self::solve(rules, 50000000000)
+    }
+}
+
+/// Register this day with the solver registry
+pub fn register(registry: &mut Registry) {
+    registry.register::<Day12, _, _>(2018, 12, "Subterranean Sustainability");
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-    use self::test::Bencher;
+    use aoc_base::bench::bench;
     use super::Day12;
     use aoc_base::AoC;
 
@@ -121,6 +145,11 @@ mod tests {
                              ###.# => #\n\
                              ####. => #";
 
+    #[test]
+    fn test_fixtures() {
+        aoc_base::fixture::test_day(super::register, include_str!("../fixtures.toml"));
+    }
+
     #[test]
     fn test_compare_pattern() {
         let a = (1..5).collect();
@@ -133,8 +162,9 @@ mod tests {
         assert_eq!(Day12::task_a(TEST_DATA).unwrap(), 325);
     }
 
-    #[bench]
-    fn bench_a(b: &mut Bencher) {
-        b.iter(test_a)
+    #[test]
+    #[ignore]
+    fn bench_a() {
+        bench("bench_a", |b| b.iter(test_a));
     }
 }
@@ -0,0 +1,186 @@
+//! What the event page of a year tells about the account of a session
+
+use crate::config::Config;
+use crate::fetch::FETCH;
+use crate::input::FetchError;
+use crate::output::{serialize, Format};
+use serde_derive::Serialize;
+use std::error::Error;
+
+/// The state of an account in one year, as far as the session lets us see it
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct Account {
+    pub year: u32,
+    /// Missing if the session isn't valid
+    pub user: Option<String>,
+    pub stars: Option<u32>,
+    pub unlocked: Vec<u8>,
+    /// Days with both parts solved
+    pub completed: Vec<u8>,
+    /// Days with only part 1 solved
+    pub half_completed: Vec<u8>,
+}
+
+pub fn event_url(year: u32, config: &Config) -> String {
+    format!("{}/{}", config.url, year)
+}
+
+/// The text between `start` and the next tag
+fn text_after<'a>(html: &'a str, start: &str) -> Option<&'a str> {
+    let from = html.find(start)? + start.len();
+    let to = from + html[from..].find('<')?;
+    Some(html[from..to].trim())
+}
+
+fn parse_event_page(year: u32, html: &str) -> Account {
+    let mut account = Account {
+        year,
+        user: text_after(html, "<div class=\"user\">").map(String::from),
+        stars: text_after(html, "<span class=\"star-count\">")
+            .and_then(|s| s.trim_end_matches('*').parse().ok()),
+        ..Account::default()
+    };
+
+    // Unlocked days link to their puzzle from the calendar, the others are plain spans
+    let link = format!("href=\"/{}/day/", year);
+    for tag in html.split('<').filter(|tag| tag.starts_with("a ")) {
+        let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+        let day = tag
+            .find(&link)
+            .map(|i| &tag[i + link.len()..])
+            .and_then(|rest| rest[..rest.find('"')?].parse::<u8>().ok());
+        let day = match day {
+            Some(day) if !account.unlocked.contains(&day) => day,
+            _ => continue,
+        };
+        account.unlocked.push(day);
+        if tag.contains("calendar-verycomplete") {
+            account.completed.push(day);
+        } else if tag.contains("calendar-complete") {
+            account.half_completed.push(day);
+        }
+    }
+    account.unlocked.sort();
+    account.completed.sort();
+    account.half_completed.sort();
+    account
+}
+
+/// Look up the account of the session on the event page of a year
+pub fn whoami(year: u32, config: &Config) -> Result<Account, Box<dyn Error>> {
+    let url = event_url(year, config);
+    let mut resp = FETCH.send(true, |client| {
+        client
+            .get(&url)
+            .header("cookie", format!("session={}", config.session))
+    })?;
+    let body = resp.text()?;
+    match resp.status().as_u16() {
+        200 => Ok(parse_event_page(year, &body)),
+        404 => Err(format!("There is no event for {}", year).into()),
+        status @ 500..=599 => Err(FetchError::Server(status).into()),
+        status => Err(FetchError::Unexpected(format!("HTTP {}", status)).into()),
+    }
+}
+
+/// Write days as ranges, e.g. "1-5, 7"
+fn format_days(days: &[u8]) -> String {
+    let mut ranges: Vec<(u8, u8)> = vec![];
+    for &day in days {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == day => *end = day,
+            _ => ranges.push((day, day)),
+        }
+    }
+    let ranges: Vec<String> = ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect();
+    if ranges.is_empty() {
+        "none".to_string()
+    } else {
+        ranges.join(", ")
+    }
+}
+
+pub fn run_whoami(year: u32, config: &Config, format: Format) -> Result<(), Box<dyn Error>> {
+    let account = whoami(year, config)?;
+    if format.is_structured() {
+        println!("{}", serialize(format, &account));
+        return Ok(());
+    }
+
+    match &account.user {
+        Some(user) => {
+            println!("Logged in as {}", user);
+            if let Some(stars) = account.stars {
+                println!("Stars in {}: {}", year, stars);
+            }
+        }
+        None => println!("Not logged in, the session is invalid or has expired"),
+    }
+    println!("Unlocked days: {}", format_days(&account.unlocked));
+    if account.user.is_some() {
+        println!("Completed:     {}", format_days(&account.completed));
+        println!("Part 1 only:   {}", format_days(&account.half_completed));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve_config;
+
+    const LOGGED_IN: &str = "<html><header>\
+        <div class=\"user\">hulthe <span class=\"star-count\">3*</span></div>\
+        </header><main><pre class=\"calendar\">\
+        <a aria-label=\"Day 1, two stars\" href=\"/2018/day/1\" class=\"calendar-day1 calendar-verycomplete\">1</a>\
+        <a aria-label=\"Day 2, one star\" href=\"/2018/day/2\" class=\"calendar-day2 calendar-complete\">2</a>\
+        <a aria-label=\"Day 3\" href=\"/2018/day/3\" class=\"calendar-day3\">3</a>\
+        <span class=\"calendar-day4\">4</span>\
+        </pre></main></html>";
+
+    const LOGGED_OUT: &str = "<html><header>\
+        <a href=\"/2018/auth/login\">[Log In]</a></header><main><pre class=\"calendar\">\
+        <a aria-label=\"Day 1\" href=\"/2018/day/1\" class=\"calendar-day1\">1</a>\
+        </pre></main></html>";
+
+    fn stub_server(status: u16, body: &'static str) -> Config {
+        serve_config(status, body).0
+    }
+
+    #[test]
+    fn test_whoami() {
+        let account = whoami(2018, &stub_server(200, LOGGED_IN)).unwrap();
+        assert_eq!(
+            account,
+            Account {
+                year: 2018,
+                user: Some("hulthe".into()),
+                stars: Some(3),
+                unlocked: vec![1, 2, 3],
+                completed: vec![1],
+                half_completed: vec![2],
+            }
+        );
+
+        let account = whoami(2018, &stub_server(200, LOGGED_OUT)).unwrap();
+        assert_eq!(account.user, None);
+        assert_eq!(account.unlocked, vec![1]);
+
+        assert!(whoami(2018, &stub_server(404, "")).is_err());
+    }
+
+    #[test]
+    fn test_format_days() {
+        assert_eq!(format_days(&[1, 2, 3, 5, 7, 8]), "1-3, 5, 7-8");
+        assert_eq!(format_days(&[]), "none");
+    }
+}
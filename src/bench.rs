@@ -0,0 +1,212 @@
+use crate::config::{load_config, Config};
+use crate::days::parse_days;
+use crate::input::get_input;
+use aoc_base::bench::{Bencher, Stats};
+use aoc_base::{Part, Registry, Solver};
+use clap::ArgMatches;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// Relative change of the mean which is considered noise
+const NOISE_THRESHOLD: f64 = 0.05;
+
+/// The result of a benchmark as saved in a baseline, in nanoseconds
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Estimate {
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+/// Saved results of every benchmark, keyed by e.g. "day01/parse"
+type Baseline = BTreeMap<String, Estimate>;
+
+/// How a benchmark compares to its baseline, with the relative change of the mean
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    New,
+    Unchanged(f64),
+    Improved(f64),
+    Regressed(f64),
+}
+
+pub fn compare(baseline: Option<&Estimate>, stats: &Stats) -> Change {
+    let baseline = match baseline {
+        Some(baseline) => baseline,
+        None => return Change::New,
+    };
+
+    let diff = stats.mean - baseline.mean;
+    let relative = diff / baseline.mean;
+    let significant =
+        relative.abs() > NOISE_THRESHOLD && diff.abs() > baseline.stddev + stats.stddev;
+    match (significant, diff > 0.0) {
+        (false, _) => Change::Unchanged(relative),
+        (true, true) => Change::Regressed(relative),
+        (true, false) => Change::Improved(relative),
+    }
+}
+
+/// Baselines are kept apart per year, as the days of each year share names
+fn baseline_path(year: u32, name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+    Ok(xdg_dirs.place_data_file(format!("baselines/{}/{}.ron", year, name))?)
+}
+
+fn load_baseline(year: u32, name: &str) -> Result<Baseline, Box<dyn Error>> {
+    let mut data = String::new();
+    match File::open(baseline_path(year, name)?) {
+        Ok(mut file) => file.read_to_string(&mut data)?,
+        Err(_) => return Ok(Baseline::new()),
+    };
+    Ok(ron::de::from_str(&data)?)
+}
+
+fn save_baseline(year: u32, name: &str, results: Baseline) -> Result<(), Box<dyn Error>> {
+    // Keep the results of days which weren't benchmarked this time
+    let mut baseline = load_baseline(year, name)?;
+    baseline.extend(results);
+
+    let mut file = File::create(baseline_path(year, name)?)?;
+    file.write_all(ron::ser::to_string(&baseline)?.as_bytes())?;
+    Ok(())
+}
+
+fn describe_change(change: &Change) -> String {
+    match change {
+        Change::New => "new".to_string(),
+        Change::Unchanged(r) => format!("{:+.2}% no change", r * 100.0),
+        Change::Improved(r) => format!("{:+.2}% improved", r * 100.0),
+        Change::Regressed(r) => format!("{:+.2}% REGRESSED", r * 100.0),
+    }
+}
+
+/// Benchmark the parse step and both parts of a day on its real input
+fn bench_day(
+    solver: &Solver,
+    config: &Config,
+    baseline: &Baseline,
+    results: &mut Baseline,
+) -> Result<usize, Box<dyn Error>> {
+    let input = get_input(solver.year, solver.day, config)?;
+    let parsed = solver.parse(&input)?;
+
+    let mut regressions = 0;
+    let mut report = |step: &str, b: Bencher| {
+        let name = format!("{}/{}", solver.name(), step);
+        let stats = b.stats().expect("Bencher::iter not called");
+
+        let change = compare(baseline.get(&name), &stats);
+        if let Change::Regressed(_) = change {
+            regressions += 1;
+        }
+        println!("{:12} {}   {}", name, stats, describe_change(&change));
+
+        results.insert(
+            name,
+            Estimate {
+                mean: stats.mean,
+                stddev: stats.stddev,
+            },
+        );
+    };
+
+    let mut b = Bencher::new();
+    b.iter(|| solver.parse(&input));
+    report("parse", b);
+
+    for &part in &[Part::A, Part::B] {
+        let mut b = Bencher::new();
+        b.iter(|| solver.solve(part, &parsed));
+        report(&part.to_string().to_lowercase(), b);
+    }
+
+    Ok(regressions)
+}
+
+/// Benchmark the selected days of a year, comparing them to a saved baseline
+pub fn run_benches(
+    registry: &Registry,
+    year: u32,
+    matches: &ArgMatches,
+) -> Result<(), Box<dyn Error>> {
+    if cfg!(debug_assertions) {
+        eprintln!("Warning: benchmarking a debug build, use --release");
+    }
+
+    let solvers: Vec<&Solver> = match matches.values_of("days") {
+        Some(days) => parse_days(&days.collect::<Vec<_>>().join(","))?
+            .into_iter()
+            .map(|day| {
+                registry.get(year, day).ok_or_else(|| {
+                    format!("No solver registered for day {} of {}", day, year).into()
+                })
+            })
+            .collect::<Result<_, Box<dyn Error>>>()?,
+        None => registry.year(year).collect(),
+    };
+    if solvers.is_empty() {
+        return Err(format!("No solvers registered for {}", year).into());
+    }
+
+    let config = load_config()?;
+    let baseline = load_baseline(year, matches.value_of("baseline").unwrap())?;
+    let mut results = Baseline::new();
+    let mut regressions = 0;
+    for solver in solvers {
+        match bench_day(solver, &config, &baseline, &mut results) {
+            Ok(n) => regressions += n,
+            Err(e) => println!("{:12} Error: {}", solver.name(), e),
+        }
+    }
+
+    save_baseline(year, matches.value_of("save-baseline").unwrap(), results)?;
+    if regressions > 0 {
+        println!("{} benchmarks regressed", regressions);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(mean: f64, stddev: f64) -> Stats {
+        Stats {
+            mean,
+            stddev,
+            median: mean,
+            outliers: 0,
+            iterations: 100,
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let base = Estimate {
+            mean: 1000.0,
+            stddev: 10.0,
+        };
+        assert_eq!(compare(None, &stats(1000.0, 10.0)), Change::New);
+        assert_eq!(
+            compare(Some(&base), &stats(1020.0, 10.0)),
+            Change::Unchanged(0.02)
+        );
+        assert_eq!(
+            compare(Some(&base), &stats(1500.0, 10.0)),
+            Change::Regressed(0.5)
+        );
+        assert_eq!(
+            compare(Some(&base), &stats(500.0, 10.0)),
+            Change::Improved(-0.5)
+        );
+        // A large change within the noise of the measurements isn't significant
+        assert_eq!(
+            compare(Some(&base), &stats(1100.0, 200.0)),
+            Change::Unchanged(0.1)
+        );
+    }
+}
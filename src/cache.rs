@@ -0,0 +1,465 @@
+//! Layout of the input cache, under `$XDG_CACHE_HOME/aoc_2018`:
+//!
+//! ```text
+//! v2/<year>/<day>/<session>          the puzzle input
+//! v2/<year>/<day>/<session>.meta     when and where the input was fetched, in RON
+//! v2/<year>/<day>/<session>.answers  the recorded answers, see ledger.rs
+//! v2/<year>/<day>/<session>.puzzle   the puzzle description, see puzzle.rs
+//! ```
+//!
+//! `<session>` is the hex encoded SHA-256 digest of the session cookie.
+//! Entries of the old layout, `<year>/<day>/<session>` named by `DefaultHasher`,
+//! are moved to the new one the first time they are looked up.
+
+use crate::config::{load_config, Config};
+use crate::input::{input_url, read_input, write_input_to_cache};
+use clap::ArgMatches;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of the cache layout, bump when changing it
+const LAYOUT: &str = "v2";
+
+/// Where a cached input came from
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Meta {
+    /// Seconds since the unix epoch
+    pub fetched: u64,
+    pub length: usize,
+    pub source: String,
+}
+
+impl Meta {
+    pub fn new(input: &str, source: String) -> Meta {
+        Meta {
+            fetched: unix_time(SystemTime::now()),
+            length: input.len(),
+            source,
+        }
+    }
+}
+
+fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn session_digest(session: &str) -> String {
+    hex::encode(Sha256::digest(session.as_bytes()))
+}
+
+/// The name of a session in the old cache layout
+fn legacy_session_hash(session: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    session.hash(&mut hasher);
+    hex::encode(hasher.finish().to_be_bytes())
+}
+
+pub fn meta_path(cache_path: &Path) -> PathBuf {
+    cache_path.with_extension("meta")
+}
+
+/// Write a file by renaming a temporary one, so readers never see a partial file
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".tmp{}", process::id()));
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = File::create(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+pub fn write_meta(cache_path: &Path, meta: &Meta) -> Result<(), Box<dyn Error>> {
+    write_atomic(
+        &meta_path(cache_path),
+        ron::ser::to_string(meta)?.as_bytes(),
+    )?;
+    Ok(())
+}
+
+pub fn read_meta(cache_path: &Path) -> Result<Meta, Box<dyn Error>> {
+    let data = fs::read_to_string(meta_path(cache_path))?;
+    Ok(ron::de::from_str(&data)?)
+}
+
+/// Move an entry of the old layout, along with its answers, to `path`
+fn migrate(legacy: &Path, path: &Path) -> io::Result<()> {
+    fs::rename(legacy, path)?;
+    let answers = legacy.with_extension("answers");
+    if answers.exists() {
+        fs::rename(answers, path.with_extension("answers"))?;
+    }
+    Ok(())
+}
+
+/// Path of the cached input for the session of the given config
+pub fn cache_path(year: u32, day: u8, config: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+
+    let cache_folder = format!("{}/{}/{}", LAYOUT, year, day);
+    let mut cache_path = xdg_dirs.create_cache_directory(cache_folder)?;
+    cache_path.push(session_digest(&config.session));
+
+    let legacy = format!("{}/{}/{}", year, day, legacy_session_hash(&config.session));
+    if let Some(legacy) = xdg_dirs.find_cache_file(legacy) {
+        if !cache_path.exists() {
+            let fetched = fs::metadata(&legacy)?.modified()?;
+            migrate(&legacy, &cache_path)?;
+            let meta = Meta {
+                fetched: unix_time(fetched),
+                length: fs::metadata(&cache_path)?.len() as usize,
+                source: input_url(year, day, config),
+            };
+            write_meta(&cache_path, &meta)?;
+        }
+    }
+
+    Ok(cache_path)
+}
+
+/// A cached input
+#[derive(Debug, PartialEq)]
+pub struct Entry {
+    pub year: u32,
+    pub day: u8,
+    pub session: String,
+    pub path: PathBuf,
+}
+
+/// Find every cached input under the root of the layout
+fn scan(root: &Path) -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+    let numbered = |dir: &Path| -> io::Result<Vec<(u32, PathBuf)>> {
+        let mut found = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let number = path.file_name().and_then(|n| n.to_str()?.parse().ok());
+            if let (Some(number), true) = (number, path.is_dir()) {
+                found.push((number, path));
+            }
+        }
+        Ok(found)
+    };
+
+    if !root.is_dir() {
+        return Ok(entries);
+    }
+    for (year, year_dir) in numbered(root)? {
+        for (day, day_dir) in numbered(&year_dir)? {
+            for file in fs::read_dir(day_dir)? {
+                let path = file?.path();
+                // Only the inputs themselves have no extension
+                if path.extension().is_some() || !path.is_file() {
+                    continue;
+                }
+                let session = match path.file_name().and_then(|n| n.to_str()) {
+                    Some(session) => session.to_owned(),
+                    None => continue,
+                };
+                entries.push(Entry {
+                    year,
+                    day: day as u8,
+                    session,
+                    path,
+                });
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| (a.year, a.day, &a.session).cmp(&(b.year, b.day, &b.session)));
+    Ok(entries)
+}
+
+pub fn entries() -> Result<Vec<Entry>, Box<dyn Error>> {
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+    Ok(scan(&xdg_dirs.get_cache_home().join(LAYOUT))?)
+}
+
+fn format_age(fetched: u64) -> String {
+    let secs = unix_time(SystemTime::now()).saturating_sub(fetched);
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// The session of the config, if there is one
+fn current_session() -> Option<String> {
+    load_config().ok().map(|c| session_digest(&c.session))
+}
+
+/// Select entries by year, day and session digest prefix
+fn select<'a>(
+    entries: &'a [Entry],
+    year: Option<u32>,
+    day: Option<u8>,
+    session: Option<&str>,
+) -> impl Iterator<Item = &'a Entry> + 'a {
+    let session = session.map(str::to_owned);
+    entries.iter().filter(move |e| {
+        year.map(|year| e.year == year).unwrap_or(true)
+            && day.map(|day| e.day == day).unwrap_or(true)
+            && session
+                .as_ref()
+                .map(|s| e.session.starts_with(s.as_str()))
+                .unwrap_or(true)
+    })
+}
+
+fn list(year: Option<u32>) -> Result<(), Box<dyn Error>> {
+    let current = current_session();
+    println!(
+        "{:4} {:>3}  {:13} {:>8}  Fetched",
+        "Year", "Day", "Session", "Bytes"
+    );
+    let entries = entries()?;
+    for entry in select(&entries, year, None, None) {
+        let (length, fetched) = match read_meta(&entry.path) {
+            Ok(meta) => (meta.length.to_string(), format_age(meta.fetched)),
+            Err(_) => (fs::metadata(&entry.path)?.len().to_string(), "?".into()),
+        };
+        let marker = if Some(&entry.session) == current.as_ref() {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{:4} {:>3}  {:12}{} {:>8}  {}",
+            entry.year,
+            entry.day,
+            &entry.session[..12.min(entry.session.len())],
+            marker,
+            length,
+            fetched
+        );
+    }
+    Ok(())
+}
+
+fn show(year: u32, day: u8, session: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let current = current_session();
+    let session = session
+        .or(current.as_deref())
+        .ok_or("No config.toml to take the session from, use --session")?;
+
+    let entries = entries()?;
+    let mut found = select(&entries, Some(year), Some(day), Some(session));
+    match (found.next(), found.next()) {
+        (Some(entry), None) => {
+            print!("{}", fs::read_to_string(&entry.path)?);
+            Ok(())
+        }
+        (None, _) => Err(format!("No cached input for day {} of {}", day, year).into()),
+        (Some(_), Some(_)) => Err("The session prefix is ambiguous".into()),
+    }
+}
+
+fn purge(year: Option<u32>, day: Option<u8>, session: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let entries = entries()?;
+    let mut purged = 0;
+    for entry in select(&entries, year, day, session) {
+        // The recorded answers are kept, they still hold for the input
+        fs::remove_file(&entry.path)?;
+        for path in &[meta_path(&entry.path), entry.path.with_extension("puzzle")] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        purged += 1;
+    }
+    println!("Purged {} cached inputs", purged);
+    Ok(())
+}
+
+/// Cache `input` at `cache_path`, refusing to replace another input unless
+/// `force` is set. The answers recorded for the old input don't apply to a
+/// different one, so they're deleted along with it.
+fn store_import(
+    cache_path: &Path,
+    input: &str,
+    meta: &Meta,
+    force: bool,
+) -> Result<(), Box<dyn Error>> {
+    if cache_path.exists() {
+        if !force {
+            return Err("The day already has a cached input, use --force to replace it".into());
+        }
+        if fs::read_to_string(cache_path)? != input {
+            let answers = cache_path.with_extension("answers");
+            if answers.exists() {
+                fs::remove_file(answers)?;
+            }
+        }
+    }
+    write_input_to_cache(cache_path, input, meta)
+}
+
+fn import(year: u32, day: u8, path: &str, force: bool) -> Result<(), Box<dyn Error>> {
+    let config = load_config()?;
+    let input = read_input(path)?;
+    if input.trim().is_empty() {
+        return Err(format!("{} is empty", path).into());
+    }
+
+    let cache_path = cache_path(year, day, &config)?;
+    let meta = Meta::new(&input, format!("imported from {}", path));
+    store_import(&cache_path, &input, &meta, force)?;
+    println!("Imported {} as the input of day {} of {}", path, day, year);
+    Ok(())
+}
+
+/// Manage the cached inputs. Only the year given with `--year` is listed or
+/// purged, while single days are taken from it or the default year.
+pub fn run_cache(
+    matches: &ArgMatches,
+    year: Option<u32>,
+    default_year: u32,
+) -> Result<(), Box<dyn Error>> {
+    let day = |m: &ArgMatches| -> Result<Option<u8>, Box<dyn Error>> {
+        Ok(match m.value_of("day") {
+            Some(day) => Some(day.parse()?),
+            None => None,
+        })
+    };
+
+    match matches.subcommand() {
+        ("list", Some(_)) => list(year),
+        ("show", Some(m)) => show(
+            year.unwrap_or(default_year),
+            day(m)?.unwrap(),
+            m.value_of("session"),
+        ),
+        ("purge", Some(m)) => {
+            let (day, session) = (day(m)?, m.value_of("session"));
+            if day.is_none() && session.is_none() && !m.is_present("all") {
+                return Err("Select what to purge with --day, --session or --all".into());
+            }
+            purge(year.or(day.map(|_| default_year)), day, session)
+        }
+        ("import", Some(m)) => import(
+            year.unwrap_or(default_year),
+            day(m)?.unwrap(),
+            m.value_of("file").unwrap(),
+            m.is_present("force"),
+        ),
+        _ => Err(matches.usage().into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_session_digest() {
+        assert_eq!(
+            session_digest("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_migrate() {
+        let dir = env::temp_dir().join(format!("aoc_migrate_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (legacy, path) = (dir.join("0123456789abcdef"), dir.join("digest"));
+        fs::write(&legacy, "+1\n").unwrap();
+        fs::write(legacy.with_extension("answers"), "(a: None, b: None)").unwrap();
+
+        migrate(&legacy, &path).unwrap();
+        assert!(!legacy.exists());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "+1\n");
+        assert!(path.with_extension("answers").exists());
+
+        let meta = Meta::new("+1\n", "http://localhost/2018/day/1/input".into());
+        write_meta(&path, &meta).unwrap();
+        let data = fs::read_to_string(meta_path(&path)).unwrap();
+        assert_eq!(ron::de::from_str::<Meta>(&data).unwrap(), meta);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = env::temp_dir().join(format!("aoc_write_atomic_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input");
+        write_atomic(&path, b"+1\n").unwrap();
+        write_atomic(&path, b"+2\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "+2\n");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_import() {
+        let dir = env::temp_dir().join(format!("aoc_import_{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("digest");
+        let answers = path.with_extension("answers");
+        let meta = |input: &str| Meta::new(input, "imported from test".into());
+        store_import(&path, "+1\n", &meta("+1\n"), false).unwrap();
+        fs::write(&answers, "(a: Some(\"1\"), b: None)").unwrap();
+
+        assert!(store_import(&path, "+2\n", &meta("+2\n"), false).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "+1\n");
+        // The same input keeps its answers
+        store_import(&path, "+1\n", &meta("+1\n"), true).unwrap();
+        assert!(answers.exists());
+        store_import(&path, "+2\n", &meta("+2\n"), true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "+2\n");
+        assert!(!answers.exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_scan() {
+        let root = env::temp_dir().join(format!("aoc_scan_{}", process::id()));
+        let inputs = [
+            ("2018", "1", "aaaa"),
+            ("2018", "1", "bbbb"),
+            ("2018", "12", "aaaa"),
+            ("2019", "1", "aaaa"),
+        ];
+        for (year, day, session) in &inputs {
+            let dir = root.join(year).join(day);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(session), "+1\n").unwrap();
+            fs::write(dir.join(session).with_extension("answers"), "()").unwrap();
+            fs::write(dir.join(session).with_extension("meta"), "()").unwrap();
+        }
+
+        let entries = scan(&root).unwrap();
+        let found: Vec<_> = entries
+            .iter()
+            .map(|e| (e.year, e.day, e.session.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2018, 1, "aaaa"),
+                (2018, 1, "bbbb"),
+                (2018, 12, "aaaa"),
+                (2019, 1, "aaaa")
+            ]
+        );
+        assert_eq!(select(&entries, Some(2018), Some(1), None).count(), 2);
+        assert_eq!(select(&entries, None, Some(1), None).count(), 3);
+        assert_eq!(select(&entries, Some(2018), None, Some("a")).count(), 2);
+        assert_eq!(select(&entries, None, Some(12), Some("b")).count(), 0);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,17 +1,303 @@
+//! The config is looked up in order from the `--config` flag, the `AOC_SESSION`
+//! and `AOC_URL` environment variables, `./config.toml`, and finally
+//! `$XDG_CONFIG_HOME/aoc_2018/config.toml`. The first one found is used.
+//!
+//! A config file can hold named profiles for several accounts, each with its
+//! own session and optionally its own url:
+//!
+//! ```toml
+//! url="https://adventofcode.com"
+//! session="abc123"
+//!
+//! [profiles.alice]
+//! session="def456"
+//! ```
+//!
+//! Selecting a profile skips the environment variables, since they can't hold any.
+
 use serde_derive::Deserialize;
+use std::collections::BTreeMap;
 use std::error::Error;
-use std::fs::File;
-use std::io::Read;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const FILE_NAME: &str = "config.toml";
 
 #[derive(Deserialize)]
 pub struct Config {
+    #[serde(default = "default_url")]
     pub url: String,
     pub session: String,
 }
 
-pub fn load_config() -> Result<Config, Box<Error>> {
-    let mut config_data = String::new();
-    let mut file = File::open("config.toml")?;
-    file.read_to_string(&mut config_data)?;
-    Ok(toml::from_str(&config_data)?)
+/// A config file as written, before a profile is selected
+#[derive(Deserialize)]
+struct ConfigFile {
+    url: Option<String>,
+    session: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Deserialize)]
+struct Profile {
+    url: Option<String>,
+    session: String,
+}
+
+fn default_url() -> String {
+    "https://adventofcode.com".into()
+}
+
+/// Where the config was found
+#[derive(Debug, PartialEq)]
+pub enum Source {
+    Flag(PathBuf),
+    Env,
+    WorkingDir(PathBuf),
+    Xdg(PathBuf),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Source::Flag(path) => write!(f, "{} (--config)", path.display()),
+            Source::Env => write!(f, "environment variables AOC_SESSION and AOC_URL"),
+            Source::WorkingDir(path) | Source::Xdg(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The path given with `--config`, if any
+static PATH: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// The profile given with `--profile`, if any
+static PROFILE: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn set_config_path(path: Option<PathBuf>) {
+    *PATH.lock().unwrap_or_else(|e| e.into_inner()) = path;
+}
+
+pub fn set_profile(profile: Option<String>) {
+    *PROFILE.lock().unwrap_or_else(|e| e.into_inner()) = profile;
+}
+
+fn read_file(path: &Path, profile: Option<&str>) -> Result<Config, Box<dyn Error>> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let file: ConfigFile =
+        toml::from_str(&data).map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+
+    let names = || file.profiles.keys().cloned().collect::<Vec<_>>().join(", ");
+    let (url, session) = match profile {
+        Some(name) => {
+            let profile = file.profiles.get(name).ok_or_else(|| {
+                format!(
+                    "{} has no profile named {:?}, it has: {}",
+                    path.display(),
+                    name,
+                    names()
+                )
+            })?;
+            (profile.url.clone(), profile.session.clone())
+        }
+        None => match &file.session {
+            Some(session) => (None, session.clone()),
+            None if !file.profiles.is_empty() => {
+                return Err(format!(
+                    "{} has no default session, select one of its profiles with --profile: {}",
+                    path.display(),
+                    names()
+                )
+                .into())
+            }
+            None => return Err(format!("{} has no session", path.display()).into()),
+        },
+    };
+
+    Ok(Config {
+        url: url.or_else(|| file.url.clone()).unwrap_or_else(default_url),
+        session,
+    })
+}
+
+/// Find the config among the given places, in order
+fn discover_in(
+    flag: Option<&Path>,
+    profile: Option<&str>,
+    env: impl Fn(&str) -> Option<String>,
+    working_dir: &Path,
+    xdg: Option<PathBuf>,
+) -> Result<(Config, Source), Box<dyn Error>> {
+    if let Some(path) = flag {
+        return Ok((read_file(path, profile)?, Source::Flag(path.to_owned())));
+    }
+
+    if let Some(session) = env("AOC_SESSION").filter(|_| profile.is_none()) {
+        let url = env("AOC_URL").unwrap_or_else(default_url);
+        return Ok((Config { url, session }, Source::Env));
+    }
+
+    let local = working_dir.join(FILE_NAME);
+    if local.is_file() {
+        return Ok((read_file(&local, profile)?, Source::WorkingDir(local)));
+    }
+
+    match xdg {
+        Some(path) if path.is_file() => Ok((read_file(&path, profile)?, Source::Xdg(path))),
+        _ => Err(format!(
+            "No {} found, pass --config, set AOC_SESSION, or create one in the \
+             working directory or {}",
+            FILE_NAME,
+            config_home().unwrap_or_else(|_| "$XDG_CONFIG_HOME/aoc_2018".into())
+        )
+        .into()),
+    }
+}
+
+fn config_home() -> Result<String, Box<dyn Error>> {
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+    Ok(xdg_dirs
+        .get_config_home()
+        .to_string_lossy()
+        .trim_end_matches('/')
+        .into())
+}
+
+/// Load the config of a profile, or the default one, along with where it was found
+fn discover(profile: Option<&str>) -> Result<(Config, Source), Box<dyn Error>> {
+    let flag = PATH.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let xdg_dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME"))?;
+    discover_in(
+        flag.as_deref(),
+        profile,
+        |name| std::env::var(name).ok().filter(|v| !v.is_empty()),
+        &std::env::current_dir()?,
+        xdg_dirs.find_config_file(FILE_NAME),
+    )
+}
+
+fn selected_profile() -> Option<String> {
+    PROFILE.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Load the config of the profile selected with `--profile`, if any
+pub fn load_config() -> Result<Config, Box<dyn Error>> {
+    load_profile(selected_profile().as_deref())
+}
+
+pub fn load_profile(profile: Option<&str>) -> Result<Config, Box<dyn Error>> {
+    Ok(discover(profile)?.0)
+}
+
+/// Hide all but the end of a session cookie
+fn mask(session: &str) -> String {
+    let len = session.chars().count();
+    let hidden = if len >= 16 { len - 4 } else { len };
+    let end: String = session.chars().skip(hidden).collect();
+    format!("{}{}", "*".repeat(hidden), end)
+}
+
+/// Print the effective config, without giving away the session
+pub fn show_config() -> Result<(), Box<dyn Error>> {
+    let profile = selected_profile();
+    let (config, source) = discover(profile.as_deref())?;
+    println!("source:  {}", source);
+    if let Some(profile) = profile {
+        println!("profile: {}", profile);
+    }
+    println!("url:     {}", config.url);
+    println!("session: {}", mask(&config.session));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_discover() {
+        let root = env::temp_dir().join(format!("aoc_config_{}", std::process::id()));
+        let cwd = root.join("cwd");
+        let xdg = root.join("xdg").join(FILE_NAME);
+        let flag = root.join("flag.toml");
+        fs::create_dir_all(&cwd).unwrap();
+        fs::create_dir_all(xdg.parent().unwrap()).unwrap();
+        fs::write(&flag, "session=\"flag\"").unwrap();
+        fs::write(&xdg, "url=\"http://xdg\"\nsession=\"xdg\"").unwrap();
+
+        let no_env = |_: &str| None;
+        let env = |name: &str| match name {
+            "AOC_SESSION" => Some("env".to_string()),
+            _ => None,
+        };
+
+        let (config, source) =
+            discover_in(Some(&flag), None, env, &cwd, Some(xdg.clone())).unwrap();
+        assert_eq!(
+            (config.session.as_str(), source),
+            ("flag", Source::Flag(flag))
+        );
+
+        let (config, source) = discover_in(None, None, env, &cwd, Some(xdg.clone())).unwrap();
+        assert_eq!(config.session, "env");
+        assert_eq!(config.url, default_url());
+        assert_eq!(source, Source::Env);
+
+        let (config, source) = discover_in(None, None, no_env, &cwd, Some(xdg.clone())).unwrap();
+        assert_eq!(config.url, "http://xdg");
+        assert_eq!(source, Source::Xdg(xdg.clone()));
+
+        let local = cwd.join(FILE_NAME);
+        fs::write(&local, "session=\"local\"").unwrap();
+        let (config, source) = discover_in(None, None, no_env, &cwd, Some(xdg)).unwrap();
+        assert_eq!(config.session, "local");
+        assert_eq!(source, Source::WorkingDir(local.clone()));
+
+        fs::remove_file(local).unwrap();
+        assert!(discover_in(None, None, no_env, &cwd, None).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_profiles() {
+        let dir = env::temp_dir().join(format!("aoc_profiles_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+        fs::write(
+            &path,
+            "url=\"http://main\"\n\
+             [profiles.alice]\nsession=\"a\"\n\
+             [profiles.bob]\nsession=\"b\"\nurl=\"http://bob\"\n",
+        )
+        .unwrap();
+
+        let alice = read_file(&path, Some("alice")).unwrap();
+        assert_eq!(
+            (alice.url.as_str(), alice.session.as_str()),
+            ("http://main", "a")
+        );
+        let bob = read_file(&path, Some("bob")).unwrap();
+        assert_eq!(
+            (bob.url.as_str(), bob.session.as_str()),
+            ("http://bob", "b")
+        );
+        assert!(read_file(&path, Some("carol")).is_err());
+        assert!(read_file(&path, None).is_err());
+
+        // Profiles are never looked up in the environment
+        let env = |_: &str| Some("env".to_string());
+        let (config, source) = discover_in(None, Some("bob"), env, &dir, None).unwrap();
+        assert_eq!(config.session, "b");
+        assert_eq!(source, Source::WorkingDir(path));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(mask("0123456789abcdef0123"), "****************0123");
+        assert_eq!(mask("abc"), "***");
+    }
 }
@@ -0,0 +1,471 @@
+//! A full-screen view of every day of a year, which can be run one at a time or
+//! all at once. Days run on a pool like in `all`, and report back to the UI
+//! thread over the same channel as the keys pressed.
+
+use crate::cache::cache_path;
+use crate::config::Config;
+use crate::days::{panic_message, solve_day};
+use crate::ledger::{Answers, Check};
+use crate::output::{part_name, Record};
+use crate::pool::Pool;
+use crate::timing::{format_duration, Timings};
+use aoc_base::{Part, Solver};
+use console::{style, Key, Term};
+use std::env;
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::Command;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const TICK: Duration = Duration::from_millis(100);
+const SPINNER: &[char] = &['|', '/', '-', '\\'];
+/// Width of the answer columns, longer answers are only shown in the details
+const ANSWER_WIDTH: usize = 16;
+const KEYS: &str = "up/down select   r/enter run   a run all   i open input   q quit";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Status {
+    NotRun,
+    /// With the step it's at
+    Running(String),
+    Passed,
+    Failed,
+    Regressed,
+}
+
+struct Row {
+    solver: Solver,
+    status: Status,
+    timings: Option<Timings>,
+    records: Vec<Record>,
+    answers: Option<Answers>,
+    error: Option<String>,
+}
+
+enum Event {
+    Key(Key),
+    Progress(usize, String),
+    Done(usize, Result<(Timings, Answers), String>, Vec<Record>),
+    Tick,
+}
+
+impl Row {
+    fn new(solver: Solver) -> Row {
+        Row {
+            solver,
+            status: Status::NotRun,
+            timings: None,
+            records: vec![],
+            answers: None,
+            error: None,
+        }
+    }
+
+    fn finish(&mut self, result: Result<(Timings, Answers), String>, records: Vec<Record>) {
+        self.records = records;
+        match result {
+            Ok((timings, answers)) => {
+                let regressed = self.records.iter().any(|r| r.matched == Some(false));
+                self.status = if regressed {
+                    Status::Regressed
+                } else {
+                    Status::Passed
+                };
+                self.timings = Some(timings);
+                self.answers = Some(answers);
+                self.error = None;
+            }
+            Err(e) => {
+                self.status = Status::Failed;
+                self.timings = None;
+                self.error = Some(e);
+            }
+        }
+    }
+
+    fn record(&self, part: Part) -> Option<&Record> {
+        let name = part_name(part);
+        self.records.iter().find(|r| r.part.as_ref() == Some(&name))
+    }
+
+    /// The answer of a part as it fits in the table
+    fn cell(&self, part: Part) -> String {
+        let record = match self.record(part) {
+            Some(record) => record,
+            None => return String::new(),
+        };
+        let answer = match &record.answer {
+            Some(answer) if answer.contains('\n') || answer.chars().count() > ANSWER_WIDTH - 2 => {
+                "(...)"
+            }
+            Some(answer) => answer,
+            None => "error",
+        };
+        match record.matched {
+            Some(true) => format!("{} *", answer),
+            Some(false) => format!("{} !", answer),
+            None => answer.to_string(),
+        }
+    }
+}
+
+struct Dashboard {
+    year: u32,
+    rows: Vec<Row>,
+    selected: usize,
+    tick: usize,
+    /// Shown above the keys until the next key is pressed
+    message: Option<String>,
+}
+
+/// Cut a line down to the width of the terminal
+fn fit(line: &str, width: usize) -> String {
+    line.chars().take(width).collect()
+}
+
+impl Dashboard {
+    fn status_label(&self, status: &Status) -> String {
+        let label = match status {
+            Status::NotRun => return style(format!("{:20}", "not run")).dim().to_string(),
+            Status::Running(step) => {
+                let spinner = SPINNER[self.tick % SPINNER.len()];
+                let text = fit(&format!("{} {}", spinner, step), 20);
+                return style(format!("{:20}", text)).cyan().to_string();
+            }
+            Status::Passed => style(format!("{:20}", "passed")).green(),
+            Status::Failed => style(format!("{:20}", "failed")).red(),
+            Status::Regressed => style(format!("{:20}", "regressed")).yellow(),
+        };
+        label.bold().to_string()
+    }
+
+    /// The full answers, timings and error of the selected day
+    fn details(&self) -> Vec<String> {
+        let row = &self.rows[self.selected];
+        let mut lines = vec![
+            style(format!("{}: {}", row.solver.name(), row.solver.title))
+                .bold()
+                .to_string(),
+        ];
+        if let Some(t) = &row.timings {
+            lines.push(format!(
+                "Fetched in {}, parsed in {}, solved A in {}, B in {}",
+                format_duration(t.fetch),
+                format_duration(t.parse),
+                format_duration(t.part_a),
+                format_duration(t.part_b)
+            ));
+        }
+        for &part in &[Part::A, Part::B] {
+            let record = match row.record(part) {
+                Some(record) => record,
+                None => continue,
+            };
+            match (&record.answer, &record.error) {
+                (Some(answer), _) if answer.contains('\n') => {
+                    lines.push(format!("Result {}:", part));
+                    lines.extend(answer.lines().map(String::from));
+                }
+                (Some(answer), _) => lines.push(format!("Result {}: {}", part, answer)),
+                (None, Some(error)) => lines.push(format!("Error {}: {}", part, error)),
+                (None, None) => {}
+            }
+            let check = record
+                .answer
+                .as_ref()
+                .zip(row.answers.as_ref())
+                .map(|(answer, answers)| answers.check(part, answer));
+            if let Some(Check::Regressed { expected }) = check {
+                lines.push(
+                    style(format!("REGRESSION: the recorded answer is {}", expected))
+                        .yellow()
+                        .to_string(),
+                );
+            }
+        }
+        // Failures of a part are already in its record
+        if let (Some(error), None) = (&row.error, row.records.last()) {
+            lines.extend(error.lines().map(|line| format!("Error: {}", line)));
+        }
+        lines
+    }
+
+    fn render(&self, width: usize, height: usize) -> Vec<String> {
+        let count = |status: Status| self.rows.iter().filter(|r| r.status == status).count();
+        let mut lines = vec![
+            format!(
+                "{}   {} passed, {} failed, {} regressed",
+                style(format!("Advent of Code {}", self.year)).bold(),
+                count(Status::Passed),
+                count(Status::Failed),
+                count(Status::Regressed)
+            ),
+            String::new(),
+            style(fit(
+                &format!(
+                    "  {:8}{:21}{:aw$} {:aw$} {:>10}",
+                    "Day",
+                    "Status",
+                    "A",
+                    "B",
+                    "Solve",
+                    aw = ANSWER_WIDTH
+                ),
+                width,
+            ))
+            .dim()
+            .to_string(),
+        ];
+        for (i, row) in self.rows.iter().enumerate() {
+            let marker = if i == self.selected { ">" } else { " " };
+            let solve = row
+                .timings
+                .map(|t| format_duration(t.solve()))
+                .unwrap_or_default();
+            let rest = fit(
+                &format!(
+                    "{:aw$} {:aw$} {:>10}",
+                    row.cell(Part::A),
+                    row.cell(Part::B),
+                    solve,
+                    aw = ANSWER_WIDTH
+                ),
+                width.saturating_sub(31),
+            );
+            let name = format!("{} {:8}", marker, row.solver.name());
+            let name = if i == self.selected {
+                style(name).reverse().to_string()
+            } else {
+                name
+            };
+            lines.push(format!(
+                "{}{} {}",
+                name,
+                self.status_label(&row.status),
+                rest
+            ));
+        }
+        lines.push(String::new());
+
+        // The details get what's left of the screen, above the message and keys
+        let footer = vec![
+            self.message.clone().unwrap_or_default(),
+            style(fit(KEYS, width)).dim().to_string(),
+        ];
+        let room = height.saturating_sub(lines.len() + footer.len());
+        let details = self.details();
+        let shown = details.len().min(room);
+        lines.extend(details.into_iter().take(shown));
+        if shown < room {
+            lines.extend(vec![String::new(); room - shown]);
+        }
+        lines.extend(footer);
+        lines
+    }
+
+    fn draw(&self, term: &Term) {
+        let (height, width) = term.size();
+        let frame = self.render(width as usize, height as usize);
+        // Clear every line as it's drawn, so nothing of a longer one is left
+        let frame: Vec<String> = frame.iter().map(|line| format!("{}\x1b[K", line)).collect();
+        term.write_str(&format!("\x1b[H{}\x1b[J", frame.join("\r\n")))
+            .ok();
+    }
+}
+
+/// Solve a day on a thread of its own, sending its progress and outcome as events
+fn spawn_day(
+    pool: &Pool,
+    index: usize,
+    solver: Solver,
+    config: Arc<Config>,
+    events: Sender<Event>,
+) {
+    pool.spawn(move || {
+        let mut records = vec![];
+        let progress = |step: &str| {
+            events.send(Event::Progress(index, step.into())).ok();
+        };
+        // A panicking solver fails its row, instead of leaving it running
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            solve_day(&solver, &config, &mut records, progress).map_err(|e| e.to_string())
+        }))
+        .unwrap_or_else(|e| Err(format!("Panicked: {}", panic_message(&*e))));
+        events.send(Event::Done(index, result, records)).ok();
+    });
+}
+
+/// Forward keys as events, waiting until each one is handled so that a pager
+/// started by the UI thread gets the terminal to itself
+fn read_keys(events: Sender<Event>, handled: Receiver<()>) {
+    let term = Term::stdout();
+    while let Ok(key) = term.read_key() {
+        if events.send(Event::Key(key)).is_err() || handled.recv().is_err() {
+            return;
+        }
+    }
+}
+
+/// Show the cached input of a day in `$PAGER`
+fn open_input(solver: &Solver, config: &Config) -> Result<(), Box<dyn Error>> {
+    let path = cache_path(solver.year, solver.day, config)?;
+    if !path.is_file() {
+        return Err(format!("The input of {} isn't downloaded yet", solver.name()).into());
+    }
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".into());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or("less");
+    Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .map_err(|e| format!("Could not run {}: {}", program, e))?;
+    Ok(())
+}
+
+pub fn run_dashboard(
+    year: u32,
+    solvers: Vec<Solver>,
+    config: Config,
+    jobs: usize,
+) -> Result<(), Box<dyn Error>> {
+    let term = Term::stdout();
+    if !term.is_term() {
+        return Err("The dashboard needs a terminal".into());
+    }
+    let config = Arc::new(config);
+    let pool = Pool::new(jobs);
+    let mut dashboard = Dashboard {
+        year,
+        rows: solvers.into_iter().map(Row::new).collect(),
+        selected: 0,
+        tick: 0,
+        message: None,
+    };
+
+    let (events, rx) = channel();
+    let (handled, keys_handled) = channel();
+    let keys = events.clone();
+    thread::spawn(move || read_keys(keys, keys_handled));
+    let ticks = events.clone();
+    thread::spawn(move || {
+        while ticks.send(Event::Tick).is_ok() {
+            thread::sleep(TICK);
+        }
+    });
+
+    term.write_str("\x1b[2J").ok();
+    dashboard.draw(&term);
+    for event in rx.iter() {
+        match event {
+            Event::Key(key) => {
+                dashboard.message = None;
+                let last = dashboard.rows.len() - 1;
+                let start = |i: usize, rows: &mut [Row]| {
+                    if let Status::Running(_) = rows[i].status {
+                        return;
+                    }
+                    rows[i].status = Status::Running("Queued".into());
+                    spawn_day(&pool, i, rows[i].solver, config.clone(), events.clone());
+                };
+                match key {
+                    Key::Char('q') | Key::Escape => break,
+                    Key::ArrowUp | Key::Char('k') => {
+                        dashboard.selected = dashboard.selected.saturating_sub(1)
+                    }
+                    Key::ArrowDown | Key::Char('j') => {
+                        dashboard.selected = (dashboard.selected + 1).min(last)
+                    }
+                    Key::Enter | Key::Char('r') => start(dashboard.selected, &mut dashboard.rows),
+                    Key::Char('a') => {
+                        for i in 0..=last {
+                            start(i, &mut dashboard.rows);
+                        }
+                    }
+                    Key::Char('i') => {
+                        let solver = dashboard.rows[dashboard.selected].solver;
+                        if let Err(e) = open_input(&solver, &config) {
+                            dashboard.message = Some(style(e.to_string()).red().to_string());
+                        }
+                        term.write_str("\x1b[2J").ok();
+                    }
+                    _ => {}
+                }
+                handled.send(()).ok();
+            }
+            Event::Progress(i, step) => dashboard.rows[i].status = Status::Running(step),
+            Event::Done(i, result, records) => dashboard.rows[i].finish(result, records),
+            Event::Tick => {
+                let running = dashboard
+                    .rows
+                    .iter()
+                    .any(|r| matches!(r.status, Status::Running(_)));
+                if !running {
+                    continue;
+                }
+                dashboard.tick += 1;
+            }
+        }
+        dashboard.draw(&term);
+    }
+    // Leave the last frame on the screen, with the prompt below it
+    term.write_line("").ok();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_base::Registry;
+
+    fn record(part: Part, answer: &str, matched: Option<bool>) -> Record {
+        Record {
+            day: 1,
+            profile: None,
+            part: Some(part_name(part)),
+            answer: Some(answer.into()),
+            duration: Some(0.001),
+            error: None,
+            matched,
+        }
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut registry = Registry::new();
+        aoc_2018_day01::register(&mut registry);
+        let solver = *registry.get(2018, 1).unwrap();
+        let mut row = Row::new(solver);
+
+        let records = vec![
+            record(Part::A, "3", Some(true)),
+            record(Part::B, "#..#\n.##.", None),
+        ];
+        row.finish(Ok((Timings::default(), Answers::default())), records);
+        assert_eq!(row.status, Status::Passed);
+        assert_eq!(row.cell(Part::A), "3 *");
+        assert_eq!(row.cell(Part::B), "(...)");
+
+        let records = vec![record(Part::A, "4", Some(false))];
+        row.finish(Ok((Timings::default(), Answers::default())), records);
+        assert_eq!(row.status, Status::Regressed);
+
+        row.finish(Err("No input".into()), vec![]);
+        assert_eq!(row.status, Status::Failed);
+        assert_eq!(row.cell(Part::A), "");
+
+        let dashboard = Dashboard {
+            year: 2018,
+            rows: vec![row],
+            selected: 0,
+            tick: 0,
+            message: None,
+        };
+        let details = dashboard.details();
+        assert_eq!(details.last().unwrap(), "Error: No input");
+        assert_eq!(dashboard.render(80, 24).len(), 24);
+    }
+}
@@ -0,0 +1,194 @@
+//! Selecting days, and running both parts of one as `all` and the dashboard do
+
+use crate::config::Config;
+use crate::input::get_input;
+use crate::ledger::{load_answers, Answers};
+use crate::output::Record;
+use crate::timing::{timed, Timings};
+use aoc_base::{AoCError, Cancel, Parsed, Part, Solver};
+use std::any::Any;
+use std::error::Error;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// The time each part may take, given with `--timeout`
+static TIMEOUT: Mutex<Option<Duration>> = Mutex::new(None);
+
+pub fn set_timeout(timeout: Option<Duration>) {
+    *TIMEOUT.lock().unwrap_or_else(|e| e.into_inner()) = timeout;
+}
+
+/// The message a thread panicked with
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (
+        payload.downcast_ref::<&str>(),
+        payload.downcast_ref::<String>(),
+    ) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown panic".to_string(),
+    }
+}
+
+/// Solve a part, giving up once it has taken longer than `--timeout`.
+///
+/// With a timeout the part is solved on a thread of its own, which is left
+/// behind if it doesn't finish in time. Solvers which check for cancellation
+/// then stop early, the others keep that thread busy until they're done. Such
+/// a thread isn't one of the `Pool`, so it doesn't count towards `--jobs`, and
+/// neither do the rayon threads of a part, which never see the token.
+pub fn solve_part(solver: &Solver, part: Part, parsed: &Parsed) -> Result<String, AoCError> {
+    let timeout = *TIMEOUT.lock().unwrap_or_else(|e| e.into_inner());
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return solver.solve(part, parsed),
+    };
+
+    let cancel = Cancel::with_timeout(timeout);
+    let (tx, rx) = channel();
+    let (solver, parsed, token) = (*solver, parsed.clone(), cancel.clone());
+    thread::spawn(move || {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            token.run(|| solver.solve(part, &parsed))
+        }));
+        tx.send(result.unwrap_or_else(|e| Err(AoCError::Panicked(panic_message(&*e)))))
+            .ok();
+    });
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            cancel.cancel();
+            Err(AoCError::Cancelled(format!(
+                "Timed out after {:?}",
+                timeout
+            )))
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            Err(AoCError::Panicked("the solver thread died".into()))
+        }
+    }
+}
+
+/// Parse a list of days and ranges of days, e.g. "3-7,11"
+pub fn parse_days(spec: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut days = vec![];
+    for item in spec
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let day = |s: &str| -> Result<u8, Box<dyn Error>> {
+            match s.trim().parse() {
+                Ok(day) if (1..=25).contains(&day) => Ok(day),
+                _ => Err(format!("{:?} is not a day", s).into()),
+            }
+        };
+        match item.find('-') {
+            Some(i) => {
+                let (start, end) = (day(&item[..i])?, day(&item[i + 1..])?);
+                if start > end {
+                    return Err(format!("The range {} is backwards", item).into());
+                }
+                days.extend(start..=end);
+            }
+            None => days.push(day(item)?),
+        }
+    }
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+/// Fetch, parse and solve both parts of a day, reporting each step to `status`.
+/// The record of each part is pushed as soon as it's solved, so a failing part
+/// leaves the ones before it.
+pub fn solve_day(
+    solver: &Solver,
+    config: &Config,
+    records: &mut Vec<Record>,
+    status: impl Fn(&str),
+) -> Result<(Timings, Answers), Box<dyn Error>> {
+    let mut t = Timings::default();
+
+    status("Fetching Data...");
+    let input: String = timed(&mut t.fetch, || get_input(solver.year, solver.day, config))?;
+
+    status("Parsing...");
+    let parsed = timed(&mut t.parse, || solver.parse(&input))?;
+    let answers = load_answers(solver.year, solver.day, config)?;
+
+    for &part in &[Part::A, Part::B] {
+        status(&format!("Calculating {}...", part));
+        let duration = match part {
+            Part::A => &mut t.part_a,
+            Part::B => &mut t.part_b,
+        };
+        let result = timed(duration, || solve_part(solver, part, &parsed));
+        records.push(Record::new(
+            solver.day,
+            part,
+            &result,
+            *duration,
+            Some(&answers),
+        ));
+        result?;
+    }
+    Ok((t, answers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_base::{AoC, Registry};
+    use std::time::Instant;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("3-7,11").unwrap(), vec![3, 4, 5, 6, 7, 11]);
+        assert_eq!(parse_days("11, 2,2").unwrap(), vec![2, 11]);
+        assert!(parse_days("7-3").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("a").is_err());
+    }
+
+    /// Sleeps through part A without ever checking for cancellation
+    struct Stuck;
+
+    impl AoC<u8, u8> for Stuck {
+        type Input = ();
+
+        fn parse(_input: &str) -> Result<(), AoCError> {
+            Ok(())
+        }
+
+        fn solve_a(_input: &()) -> Result<u8, AoCError> {
+            thread::sleep(Duration::from_secs(10));
+            Ok(1)
+        }
+
+        fn solve_b(_input: &()) -> Result<u8, AoCError> {
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn test_solve_part_timeout() {
+        let mut registry = Registry::new();
+        registry.register::<Stuck, _, _>(2018, 1, "Stuck");
+        let solver = registry.get(2018, 1).unwrap();
+        let parsed = solver.parse("").unwrap();
+
+        set_timeout(Some(Duration::from_millis(100)));
+        let start = Instant::now();
+        let result = solve_part(solver, Part::A, &parsed);
+        set_timeout(None);
+        match result {
+            Err(AoCError::Cancelled(reason)) => assert_eq!(reason, "Timed out after 100ms"),
+            r => panic!("Expected the part to time out, got {:?}", r),
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
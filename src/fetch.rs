@@ -0,0 +1,129 @@
+//! Every request to the Advent of Code server goes through here, so that we
+//! never send more than one request at a time and keep a distance between them.
+
+use reqwest::header::USER_AGENT;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::error::Error;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Identifies us to the server, as asked by the Advent of Code maintainers
+const AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CARGO_PKG_AUTHORS"),
+    ")"
+);
+
+pub struct Coordinator {
+    /// When the last request finished, the lock is held while a request is in flight
+    last: Mutex<Option<Instant>>,
+    interval: Duration,
+    retries: u32,
+    backoff: Duration,
+    offline: AtomicBool,
+}
+
+/// The coordinator shared by the whole program
+pub static FETCH: Coordinator =
+    Coordinator::new(Duration::from_secs(1), 3, Duration::from_millis(500));
+
+/// A failure that might not happen again if we try later
+fn is_transient(result: &Result<Response, reqwest::Error>) -> bool {
+    match result {
+        Ok(resp) => {
+            resp.status().is_server_error() || resp.status() == StatusCode::TOO_MANY_REQUESTS
+        }
+        // Connection failures and timeouts
+        Err(e) => e.is_http() || e.get_ref().is_some_and(|e| e.is::<io::Error>()),
+    }
+}
+
+impl Coordinator {
+    pub const fn new(interval: Duration, retries: u32, backoff: Duration) -> Coordinator {
+        Coordinator {
+            last: Mutex::new(None),
+            interval,
+            retries,
+            backoff,
+            offline: AtomicBool::new(false),
+        }
+    }
+
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
+    /// Send the request made by `build`, retrying transient failures if `retry` is set.
+    /// Only idempotent requests should be retried.
+    pub fn send<F>(&self, retry: bool, build: F) -> Result<Response, Box<dyn Error>>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        if self.offline.load(Ordering::SeqCst) {
+            return Err("Network access is disabled by --offline".into());
+        }
+
+        let client = Client::new();
+        let mut last = self.last.lock().unwrap_or_else(|e| e.into_inner());
+        let mut attempt = 0;
+        loop {
+            if let Some(since) = last.map(|t| t.elapsed()) {
+                if since < self.interval {
+                    thread::sleep(self.interval - since);
+                }
+            }
+
+            let result = build(&client).header(USER_AGENT, AGENT).send();
+            *last = Some(Instant::now());
+
+            if !retry || attempt >= self.retries || !is_transient(&result) {
+                return Ok(result?);
+            }
+            thread::sleep(self.backoff * 2u32.pow(attempt));
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve;
+
+    #[test]
+    fn test_retry() {
+        let (url, rx) = serve(vec![(503, "ok"), (502, "ok"), (200, "ok")]);
+        let fetch = Coordinator::new(Duration::from_millis(10), 3, Duration::from_millis(1));
+        let resp = fetch.send(true, |c| c.get(&url)).unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let requests: Vec<String> = rx.iter().take(3).collect();
+        let agent = format!("user-agent: {}", AGENT).to_lowercase();
+        assert!(requests.iter().all(|r| r.to_lowercase().contains(&agent)));
+    }
+
+    #[test]
+    fn test_no_retry() {
+        let (url, _rx) = serve(vec![(503, "ok")]);
+        let fetch = Coordinator::new(Duration::from_millis(10), 3, Duration::from_millis(1));
+        let resp = fetch.send(false, |c| c.post(&url)).unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_offline() {
+        let fetch = Coordinator::new(Duration::from_millis(10), 3, Duration::from_millis(1));
+        fetch.set_offline(true);
+        assert!(fetch.send(true, |c| c.get("http://127.0.0.1:1")).is_err());
+    }
+}
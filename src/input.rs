@@ -1,49 +1,146 @@
-use crate::config::{load_config, Config};
-use std::collections::hash_map::DefaultHasher;
+use crate::cache::{cache_path, write_atomic, write_meta, Meta};
+use crate::config::Config;
+use crate::fetch::FETCH;
 use std::error::Error;
+use std::fmt::{self, Display, Formatter};
 use std::fs::File;
-use std::hash::{Hash, Hasher};
-use std::io::{Read, Write};
-use std::path::PathBuf;
-
-fn download_input(year: u32, day: u8, config: &Config) -> Result<String, Box<Error>> {
-    let client = reqwest::Client::new();
-    let mut resp = client
-        .get(&format!("{}/{}/day/{}/input", config.url, year, day))
-        .header("cookie", format!("session={}", config.session))
-        .send()?;
-    Ok(resp.text()?)
-}
-
-fn write_input_to_cache(cache_path: &PathBuf, input: &str) -> Result<(), Box<Error>> {
-    let mut file = File::create(cache_path)?;
-    file.write_all(input.as_bytes())?;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub fn input_url(year: u32, day: u8, config: &Config) -> String {
+    format!("{}/{}/day/{}/input", config.url, year, day)
+}
+
+/// Why the input endpoint didn't return a puzzle input
+#[derive(Debug, PartialEq)]
+pub enum FetchError {
+    NotLoggedIn,
+    NotUnlocked,
+    Server(u16),
+    Unexpected(String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            FetchError::NotLoggedIn => {
+                write!(f, "Not logged in, check the session in your config.toml")
+            }
+            FetchError::NotUnlocked => write!(f, "The puzzle isn't unlocked yet"),
+            FetchError::Server(status) => write!(f, "Server error (HTTP {})", status),
+            FetchError::Unexpected(reason) => write!(f, "Unexpected response: {}", reason),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+/// Make sure a response of the input endpoint actually is a puzzle input
+fn check_response(status: u16, body: &str) -> Result<(), FetchError> {
+    if body.starts_with("Puzzle inputs differ by user") {
+        return Err(FetchError::NotLoggedIn);
+    }
+    match status {
+        200 => {}
+        400 => return Err(FetchError::NotLoggedIn),
+        404 => return Err(FetchError::NotUnlocked),
+        500..=599 => return Err(FetchError::Server(status)),
+        _ => return Err(FetchError::Unexpected(format!("HTTP {}", status))),
+    }
+
+    let start = body.trim_start();
+    if start.is_empty() {
+        Err(FetchError::Unexpected("empty input".into()))
+    } else if start.starts_with('<') {
+        Err(FetchError::Unexpected("got an HTML page".into()))
+    } else {
+        Ok(())
+    }
+}
+
+fn download_input(year: u32, day: u8, config: &Config) -> Result<String, Box<dyn Error>> {
+    let url = input_url(year, day, config);
+    let mut resp = FETCH.send(true, |client| {
+        client
+            .get(&url)
+            .header("cookie", format!("session={}", config.session))
+    })?;
+    let body = resp.text()?;
+    check_response(resp.status().as_u16(), &body)?;
+    Ok(body)
+}
+
+pub fn write_input_to_cache(
+    cache_path: &Path,
+    input: &str,
+    meta: &Meta,
+) -> Result<(), Box<dyn Error>> {
+    // The metadata goes first, an input without it is still usable
+    write_meta(cache_path, meta)?;
+    write_atomic(cache_path, input.as_bytes())?;
     Ok(())
 }
-fn get_input_from_cache(cache_path: &PathBuf) -> Result<String, Box<Error>> {
+
+fn get_input_from_cache(cache_path: &PathBuf) -> Result<String, Box<dyn Error>> {
     let mut cache_data = String::new();
     let mut file = File::open(cache_path)?;
     file.read_to_string(&mut cache_data)?;
     Ok(cache_data)
 }
 
-pub fn get_input(year: u32, day: u8) -> Result<String, Box<Error>> {
-    let xdg_dirs = xdg::BaseDirectories::with_prefix(env!("CARGO_PKG_NAME")).unwrap();
-    let config = load_config().expect("Could not load config");
-
-    let mut hasher = DefaultHasher::new();
-    config.session.hash(&mut hasher);
-    let session_hash = hex::encode(&hasher.finish().to_be_bytes());
+/// Read an input from a file, or from stdin if the path is "-"
+pub fn read_input(path: &str) -> Result<String, Box<dyn Error>> {
+    let mut input = String::new();
+    if path == "-" {
+        io::stdin().read_to_string(&mut input)?;
+    } else {
+        File::open(path)
+            .map_err(|e| format!("Could not open {}: {}", path, e))?
+            .read_to_string(&mut input)?;
+    }
+    Ok(input)
+}
 
-    let cache_folder = format!("{}/{}", year, day);
-    let mut cache_path = xdg_dirs.create_cache_directory(cache_folder)?;
-    cache_path.push(session_hash);
+pub fn get_input(year: u32, day: u8, config: &Config) -> Result<String, Box<dyn Error>> {
+    let cache_path = cache_path(year, day, config)?;
 
     if let Ok(input) = get_input_from_cache(&cache_path) {
         Ok(input)
     } else {
-        let input = download_input(year, day, &config)?;
-        write_input_to_cache(&cache_path, &input)?;
+        let input = download_input(year, day, config)
+            .map_err(|e| format!("Could not fetch the input of day {}: {}", day, e))?;
+        let meta = Meta::new(&input, input_url(year, day, config));
+        write_input_to_cache(&cache_path, &input, &meta)?;
         Ok(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_response() {
+        assert_eq!(check_response(200, "+1\n-2\n"), Ok(()));
+        assert_eq!(
+            check_response(
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n"
+            ),
+            Err(FetchError::NotLoggedIn)
+        );
+        assert_eq!(
+            check_response(
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!"
+            ),
+            Err(FetchError::NotUnlocked)
+        );
+        assert_eq!(
+            check_response(502, "Bad Gateway"),
+            Err(FetchError::Server(502))
+        );
+        assert!(check_response(200, "").is_err());
+        assert!(check_response(200, "<!DOCTYPE html>\n<html>").is_err());
+    }
+}
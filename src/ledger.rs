@@ -0,0 +1,104 @@
+use crate::cache::{cache_path, write_atomic};
+use crate::config::Config;
+use aoc_base::Part;
+use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// The known correct answers of a day, stored next to its cached input
+#[derive(Default, Serialize, Deserialize)]
+pub struct Answers {
+    pub a: Option<String>,
+    pub b: Option<String>,
+}
+
+/// How a computed answer compares to the recorded one
+#[derive(Debug, PartialEq)]
+pub enum Check {
+    Unknown,
+    Correct,
+    Regressed { expected: String },
+}
+
+impl Check {
+    /// Whether the answer matched, or None if there was nothing to compare to
+    pub fn matched(&self) -> Option<bool> {
+        match self {
+            Check::Unknown => None,
+            Check::Correct => Some(true),
+            Check::Regressed { .. } => Some(false),
+        }
+    }
+}
+
+impl Answers {
+    pub fn get(&self, part: Part) -> Option<&String> {
+        match part {
+            Part::A => self.a.as_ref(),
+            Part::B => self.b.as_ref(),
+        }
+    }
+
+    pub fn check(&self, part: Part, answer: &str) -> Check {
+        match self.get(part) {
+            None => Check::Unknown,
+            Some(expected) if expected == answer => Check::Correct,
+            Some(expected) => Check::Regressed {
+                expected: expected.clone(),
+            },
+        }
+    }
+}
+
+fn ledger_path(year: u32, day: u8, config: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(cache_path(year, day, config)?.with_extension("answers"))
+}
+
+pub fn load_answers(year: u32, day: u8, config: &Config) -> Result<Answers, Box<dyn Error>> {
+    let mut data = String::new();
+    match File::open(ledger_path(year, day, config)?) {
+        Ok(mut file) => file.read_to_string(&mut data)?,
+        Err(_) => return Ok(Answers::default()),
+    };
+    Ok(ron::de::from_str(&data)?)
+}
+
+pub fn record_answer(
+    year: u32,
+    day: u8,
+    config: &Config,
+    part: Part,
+    answer: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut answers = load_answers(year, day, config)?;
+    match part {
+        Part::A => answers.a = Some(answer.to_owned()),
+        Part::B => answers.b = Some(answer.to_owned()),
+    }
+
+    write_atomic(
+        &ledger_path(year, day, config)?,
+        ron::ser::to_string(&answers)?.as_bytes(),
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = ron::de::from_str(r#"(a: Some("31"), b: None)"#).unwrap();
+        assert_eq!(answers.check(Part::A, "31"), Check::Correct);
+        assert_eq!(
+            answers.check(Part::A, "32"),
+            Check::Regressed {
+                expected: "31".into()
+            }
+        );
+        assert_eq!(answers.check(Part::B, "0"), Check::Unknown);
+    }
+}
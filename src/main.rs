@@ -1,193 +1,991 @@
-#![feature(test)]
-#![feature(await_macro)]
+mod account;
+mod bench;
+mod cache;
 mod config;
+mod dashboard;
+mod days;
+mod fetch;
 mod input;
+mod ledger;
+mod output;
+mod pool;
+mod puzzle;
+mod scaffold;
+mod submit;
+#[cfg(test)]
+mod test_server;
+mod timing;
+mod watch;
 
-use aoc_2018_day01::Day01;
-use aoc_2018_day02::Day02;
-use aoc_2018_day03::Day03;
-use aoc_2018_day04::Day04;
-use aoc_2018_day05::Day05;
-use aoc_2018_day06::Day06;
-use aoc_2018_day07::Day07;
-use aoc_2018_day08::Day08;
-use aoc_2018_day09::Day09;
-use aoc_2018_day10::Day10;
-use aoc_2018_day11::Day11;
-use aoc_2018_day12::Day12;
-use aoc_base::AoC;
+use aoc_base::{AoCError, Part, Registry, Solver};
 use clap::{
-    app_from_crate, crate_authors, crate_description, crate_name, crate_version, Arg, SubCommand,
+    app_from_crate, crate_authors, crate_description, crate_name, crate_version, App, Arg,
+    ArgMatches, SubCommand,
 };
 use std::error::Error;
-use std::fmt::Display;
-use std::sync::{mpsc::channel, Arc};
-use std::thread;
-use std::time::Duration;
-
-use crate::input::get_input;
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
-use reqwest;
-
-macro_rules! setup_days {
-    ($app:ident, $d:ident) => {{
-       $app.subcommand(
-            SubCommand::with_name(&stringify!($d).to_lowercase())
-                .arg(Arg::with_name(concat!(stringify!($d), "Task")) //FIXME: task name
-                     .required(true)
-                     .possible_values(&["task_a", "task_b"])))
-    }};
-    ($app:ident, $d:ident, $($ds:ident),+) => {{
-        let tmp = setup_days!($app, $d);
-        setup_days!(tmp, $($ds),*)
-    }};
-}
-
-macro_rules! run_days_async {
-    ($vec:ident, $mp:ident, $d:ident) => {{
-    }};
-    ($vec:ident, $mp:ident, $d:ident, $($ds:ident),+) => {{
-        let spinner_style = ProgressStyle::default_spinner()
-            //.tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
-            .tick_chars("|/-\\ ")
-            .template("{prefix:.bold.dim} {spinner} {wide_msg}");
-        let pb = $mp.add(ProgressBar::new_spinner());
-        pb.set_style(spinner_style);
-        pb.set_prefix(&stringify!($d));
-        //pb.enable_steady_tick(100);
-        let handle = thread::spawn(move|| {
-            let pb = Arc::new(pb);
-            let (tx, rx) = channel();
-            let run = || -> Result<(), Box<Error>> {
-                let pb2 = pb.clone();
-                thread::spawn(move|| loop {
-                    if let Ok(_) = rx.try_recv() { return; }
-                    thread::sleep(Duration::from_millis(75));
-                    pb2.inc(1);
-                });
-
-                pb.set_message("Fetching Data...");
-                let input: String = get_input(2018, stringify!($d)[3..].parse::<u8>()?)?;
-
-                pb.set_message("Calculating A...");
-                let res_a = $d::task_a(&input)?.to_string();
-
-                pb.set_message("Calculating B...");
-                let res_b = $d::task_b(&input)?.to_string();
-
-                fn hide_long<'a>(s: &'a str) -> &'a str {
-                    if msg_is_slim(s) { s } else { "(...)" }
-                };
-
-                pb.finish_with_message(
-                    &format!("Result A: {:10}   B: {}",
-                             hide_long(&res_a),
-                             hide_long(&res_b)));
-                Ok(())
+use std::fmt::{self, Display};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::account::run_whoami;
+use crate::bench::run_benches;
+use crate::cache::{cache_path, run_cache};
+use crate::config::{load_config, load_profile, set_config_path, set_profile, show_config, Config};
+use crate::dashboard::run_dashboard;
+use crate::days::{panic_message, parse_days, set_timeout, solve_day, solve_part};
+use crate::fetch::FETCH;
+use crate::input::{get_input, read_input};
+use crate::ledger::{load_answers, record_answer, Answers, Check};
+use crate::output::{part_name, serialize, Format, Record};
+use crate::pool::Pool;
+use crate::puzzle::read_puzzle;
+use crate::scaffold::new_day;
+use crate::submit::{submit_answer, Verdict};
+use crate::timing::{format_duration, parse_duration, print_report, timed, Timings};
+use crate::watch::watch;
+use console::style;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Collect the solvers of every day crate
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    aoc_2018_day01::register(&mut registry);
+    aoc_2018_day02::register(&mut registry);
+    aoc_2018_day03::register(&mut registry);
+    aoc_2018_day04::register(&mut registry);
+    aoc_2018_day05::register(&mut registry);
+    aoc_2018_day06::register(&mut registry);
+    aoc_2018_day07::register(&mut registry);
+    aoc_2018_day08::register(&mut registry);
+    aoc_2018_day09::register(&mut registry);
+    aoc_2018_day10::register(&mut registry);
+    aoc_2018_day11::register(&mut registry);
+    aoc_2018_day12::register(&mut registry);
+    registry
+}
+
+/// Add a subcommand for each day, shared by every year that has it
+fn setup_days<'a, 'b>(mut app: App<'a, 'b>, registry: &Registry) -> App<'a, 'b> {
+    // Describe each day with its title from the latest year
+    let mut days: Vec<&Solver> = registry.iter().collect();
+    days.sort_by_key(|s| (s.day, std::cmp::Reverse(s.year)));
+    days.dedup_by_key(|s| s.day);
+    for solver in days {
+        app = app.subcommand(
+            SubCommand::with_name(&solver.name())
+                .about(solver.title)
+                .arg(
+                    Arg::with_name("part")
+                        .possible_values(&["a", "b", "both"])
+                        .default_value("both")
+                        .help("The part to solve"),
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .short("i")
+                        .takes_value(true)
+                        .value_name("PATH")
+                        .help(
+                            "Read the input from a file, or stdin if -, instead of downloading it",
+                        ),
+                ),
+        );
+    }
+    app
+}
+
+/// An error which has already been shown, only left to fail the exit status
+#[derive(Debug)]
+struct Reported;
+
+impl Display for Reported {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Failed")
+    }
+}
+
+impl Error for Reported {}
+
+/// What a day sends back to `run_all` when it's done
+struct DayReport {
+    /// Position of the day among those scheduled, which the reports are shown in
+    index: usize,
+    name: String,
+    timings: Option<Timings>,
+    records: Vec<Record>,
+}
+
+/// Queue a day on the pool, returning its spinner
+fn run_day_async(
+    pool: &Pool,
+    mp: &MultiProgress,
+    index: usize,
+    solver: Solver,
+    config: Arc<Config>,
+    profile: Option<String>,
+    report: Sender<DayReport>,
+) -> Arc<ProgressBar> {
+    let spinner_style = ProgressStyle::default_spinner()
+        //.tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ ")
+        .tick_chars("|/-\\ ")
+        .template("{prefix:.bold.dim} {spinner} {wide_msg}");
+    let pb = mp.add(ProgressBar::new_spinner());
+    pb.set_style(spinner_style);
+    match &profile {
+        Some(profile) => pb.set_prefix(&format!("{} {}", solver.name(), profile)),
+        None => pb.set_prefix(&solver.name()),
+    }
+    pb.set_message("Queued");
+    let pb = Arc::new(pb);
+    let spinner = pb.clone();
+    pool.spawn(move || {
+        let mut records = vec![];
+        let mut run = || -> Result<Timings, Box<dyn Error>> {
+            let (t, answers) =
+                solve_day(&solver, &config, &mut records, |msg| pb.set_message(msg))?;
+            let answer = |part: Part| {
+                let record = records.iter().find(|r| r.part == Some(part_name(part)));
+                record.and_then(|r| r.answer.clone()).unwrap_or_default()
             };
 
-            if let Err(e) = run() {
+            pb.finish_with_message(&format!(
+                "Result A: {:12}   B: {:12}   in {}",
+                mark_result(&answers, Part::A, &answer(Part::A)),
+                mark_result(&answers, Part::B, &answer(Part::B)),
+                format_duration(t.solve())
+            ));
+            Ok(t)
+        };
+
+        // A panicking solver fails its day, instead of taking down the others
+        let result = panic::catch_unwind(AssertUnwindSafe(&mut run))
+            .unwrap_or_else(|e| Err(format!("Panicked: {}", panic_message(&*e)).into()));
+        let timings = match result {
+            Ok(timings) => Some(timings),
+            Err(e) => {
                 pb.finish_with_message(&format!("Error: {}", e));
+                // Failures of a part are already in its record
+                if records.is_empty() {
+                    records.push(Record::failure(solver.day, None, e.to_string()));
+                }
+                None
+            }
+        };
+        for record in &mut records {
+            record.profile = profile.clone();
+        }
+        report
+            .send(DayReport {
+                index,
+                name: solver.name(),
+                timings,
+                records,
+            })
+            .ok();
+    });
+    spinner
+}
+
+/// Spin the spinners of every day from a single thread, collecting the reports
+/// of the days until all of them are done
+fn spawn_ticker(
+    spinners: Vec<Arc<ProgressBar>>,
+    reports: Receiver<DayReport>,
+) -> JoinHandle<Vec<DayReport>> {
+    thread::spawn(move || {
+        let mut done: Vec<DayReport> = vec![];
+        loop {
+            match reports.recv_timeout(Duration::from_millis(75)) {
+                Ok(report) => done.push(report),
+                Err(RecvTimeoutError::Timeout) => {
+                    for (index, pb) in spinners.iter().enumerate() {
+                        if done.iter().all(|r| r.index != index) {
+                            pb.inc(1);
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => return done,
+            }
+        }
+    })
+}
+
+fn run_all(registry: &Registry, year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let format = Format::from_matches(matches);
+    // Structured output reports the failure as a record instead
+    let fail = |e: Box<dyn Error>| -> Box<dyn Error> {
+        match format {
+            Format::Human => e,
+            _ => {
+                let record = Record::failure(0, None, e.to_string());
+                println!("{}", serialize(format, &vec![record]));
+                Reported.into()
+            }
+        }
+    };
+
+    if registry.year(year).next().is_none() {
+        return Err(fail(format!("No solvers registered for {}", year).into()));
+    }
+    let days = matches
+        .value_of("days")
+        .map(parse_days)
+        .transpose()
+        .map_err(fail)?;
+    let skip = parse_days(matches.value_of("skip").unwrap_or("")).map_err(fail)?;
+    if let Some(day) = days
+        .iter()
+        .flatten()
+        .find(|&&day| registry.get(year, day).is_none())
+    {
+        return Err(fail(
+            format!("No solver registered for day {} of {}", day, year).into(),
+        ));
+    }
+    let solvers: Vec<Solver> = registry
+        .year(year)
+        .filter(|s| days.as_ref().is_none_or(|days| days.contains(&s.day)))
+        .filter(|s| !skip.contains(&s.day))
+        .cloned()
+        .collect();
+    if solvers.is_empty() {
+        return Err(fail("Every selected day is skipped".into()));
+    }
+
+    let profiles: Option<Vec<String>> = matches
+        .values_of("profiles")
+        .map(|names| names.map(String::from).collect());
+    let configs: Vec<(Option<String>, Arc<Config>)> = match &profiles {
+        Some(names) => names
+            .iter()
+            .map(|name| Ok((Some(name.clone()), Arc::new(load_profile(Some(name))?))))
+            .collect::<Result<_, Box<dyn Error>>>()
+            .map_err(fail)?,
+        None => vec![(None, Arc::new(load_config().map_err(fail)?))],
+    };
+    let jobs: usize = matches
+        .value_of("jobs")
+        .unwrap_or("0")
+        .parse()
+        .map_err(|e| fail(Box::new(e)))?;
+
+    let start = Instant::now();
+    let mp = MultiProgress::new();
+    if format.is_structured() {
+        mp.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    // Days with parallel parts share the rayon pool, so their timings grow
+    // when other days keep it busy. Use --jobs 1 for the most exact ones.
+    let pool = Pool::new(jobs);
+    let (tx, rx) = channel();
+    let mut spinners = vec![];
+    for solver in solvers {
+        for (profile, config) in &configs {
+            let index = spinners.len();
+            let (profile, config) = (profile.clone(), config.clone());
+            let pb = run_day_async(&pool, &mp, index, solver, config, profile, tx.clone());
+            spinners.push(pb);
+        }
+    }
+    drop(tx);
+    let ticker = spawn_ticker(spinners, rx);
+    mp.join().unwrap();
+
+    // In the order the days were scheduled in, whichever finished first
+    let mut reports = ticker.join().expect("The ticker panicked");
+    reports.sort_by_key(|r| r.index);
+    let failed = reports.iter().any(|r| r.timings.is_none());
+    if format.is_structured() {
+        let records: Vec<&Record> = reports.iter().flat_map(|r| &r.records).collect();
+        println!("{}", serialize(format, &records));
+        return if failed { Err(Reported.into()) } else { Ok(()) };
+    }
+
+    println!();
+    match profiles {
+        Some(profiles) => {
+            print_side_by_side(&profiles, &reports);
+            println!("Finished in {}", format_duration(start.elapsed()));
+        }
+        None => {
+            let rows = reports.into_iter().map(|r| (r.name, r.timings)).collect();
+            print_report(rows, matches.is_present("sort-by-time"), start.elapsed());
+        }
+    }
+    match failed {
+        true => Err(Reported.into()),
+        false => Ok(()),
+    }
+}
+
+/// Show the answers of every profile next to each other, one day per row
+fn print_side_by_side(profiles: &[String], reports: &[DayReport]) {
+    let cell = |report: &DayReport| {
+        let part = |name: &str| {
+            let record = report
+                .records
+                .iter()
+                .find(|r| r.part.as_deref() == Some(name));
+            match record {
+                Some(Record {
+                    answer: Some(answer),
+                    matched,
+                    ..
+                }) => match matched {
+                    Some(true) => format!("{} *", hide_long(answer)),
+                    Some(false) => format!("{} !", hide_long(answer)),
+                    None => hide_long(answer).to_string(),
+                },
+                Some(_) => "error".to_string(),
+                None if report.records.iter().any(|r| r.error.is_some()) => "error".to_string(),
+                None => "-".to_string(),
             }
-            tx.send(()).ok();
-        });
-        $vec.push(handle);
-        run_days_async!($vec, $mp, $($ds),*);
-    }};
-}
-
-macro_rules! run_days {
-    ($matches:ident, $d:ident) => {{
-        println!("{}", $matches.usage());
-    }};
-    ($matches:ident, all, $($ds:ident),+) => {{
-            if let Some(_) = $matches.subcommand_matches("all") {
-                let mp = MultiProgress::new();
-                let mut handles: Vec<_> = vec![];
-                run_days_async!(handles, mp, $($ds),*);
-                mp.join().unwrap();
-            } else {
-                run_days!($matches, $($ds),*)
+        };
+        format!("{} / {}", part("a"), part("b"))
+    };
+
+    let mut rows: Vec<(&str, Vec<String>)> = vec![];
+    for report in reports {
+        match rows.last_mut() {
+            Some((name, cells)) if *name == report.name => cells.push(cell(report)),
+            _ => rows.push((&report.name, vec![cell(report)])),
+        }
+    }
+
+    let width = rows
+        .iter()
+        .flat_map(|(_, cells)| cells.iter().map(|c| c.len()))
+        .chain(profiles.iter().map(|p| p.len()))
+        .max()
+        .unwrap_or(0);
+    print!("{:8}", "");
+    for profile in profiles {
+        let header = format!("{:width$}", profile, width = width);
+        print!(" | {}", style(header).bold());
+    }
+    println!();
+    for (name, cells) in rows {
+        print!("{:8}", name);
+        for cell in cells {
+            print!(" | {:width$}", cell, width = width);
+        }
+        println!();
+    }
+    println!("* matches the recorded answer, ! differs from it");
+}
+
+fn run_day(solver: &Solver, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let format = Format::from_matches(matches);
+    let parts: &[Part] = match matches.value_of("part") {
+        Some("a") => &[Part::A],
+        Some("b") => &[Part::B],
+        _ => &[Part::A, Part::B],
+    };
+    let single = match parts {
+        [part] => Some(*part),
+        _ => None,
+    };
+    // Structured output reports the failure as a record instead
+    let fail = |e: String| -> Box<dyn Error> {
+        match format {
+            Format::Human => e.into(),
+            _ => {
+                let record = Record::failure(solver.day, single, e);
+                println!("{}", serialize(format, &vec![record]));
+                Reported.into()
+            }
+        }
+    };
+
+    // The config is only needed for the downloaded input
+    let path = matches.value_of("input");
+    let config = match path {
+        Some(_) => None,
+        None => match load_config() {
+            Ok(config) => Some(config),
+            Err(e) => return Err(fail(e.to_string())),
+        },
+    };
+
+    let mut t = Timings::default();
+    let input = timed(&mut t.fetch, || match (&config, path) {
+        (Some(config), _) => get_input(solver.year, solver.day, config),
+        (None, path) => read_input(path.unwrap()),
+    });
+    let input: String = match input {
+        Ok(input) => input,
+        Err(e) => return Err(fail(e.to_string())),
+    };
+    let describe = |e: &AoCError| match format {
+        Format::Human => describe_error(e, &input),
+        _ => e.to_string(),
+    };
+    let parsed = match timed(&mut t.parse, || solver.parse(&input)) {
+        Ok(parsed) => parsed,
+        Err(e) => return Err(fail(describe(&e))),
+    };
+
+    // The recorded answers only apply to the downloaded input
+    let answers = match &config {
+        None => None,
+        Some(config) => match load_answers(solver.year, solver.day, config) {
+            Ok(answers) => Some(answers),
+            Err(e) => return Err(fail(e.to_string())),
+        },
+    };
+
+    let mut records = vec![];
+    let mut solve_times = vec![];
+    let mut failed = false;
+    for &part in parts {
+        let mut solve_time = Duration::default();
+        let result = timed(&mut solve_time, || solve_part(solver, part, &parsed));
+        solve_times.push(format!("{} in {}", part, format_duration(solve_time)));
+        failed |= result.is_err();
+        if format.is_structured() {
+            records.push(Record::new(
+                solver.day,
+                part,
+                &result,
+                solve_time,
+                answers.as_ref(),
+            ));
+            continue;
+        }
+
+        // Only name the part when showing both
+        let label = match single {
+            Some(_) => String::new(),
+            None => format!(" {}", part),
+        };
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("Error{}: {}", label, describe(&e));
+                continue;
             }
-    }};
-    ($matches:ident, $d:ident, $($ds:ident),+) => {{
-        if let Some(sub_matches) = $matches.subcommand_matches(&stringify!($d).to_lowercase()) {
-            let input: String = get_input(2018, stringify!($d)[3..].parse::<u8>().unwrap()).unwrap();
-            match sub_matches.value_of(concat!(stringify!($d), "Task")) {
-                Some("task_a") => print_result($d::task_a(&input).unwrap()),
-                Some("task_b") => print_result($d::task_b(&input).unwrap()),
-                _ => unreachable!("No task selected"),
+        };
+        print_result(&format!("Result{}", label), &result);
+        match answers.as_ref().map(|answers| answers.check(part, &result)) {
+            None | Some(Check::Unknown) => {}
+            Some(Check::Correct) => println!("Matches the recorded answer"),
+            Some(Check::Regressed { expected }) => {
+                println!("REGRESSION: the recorded answer is {}", expected)
             }
-        } else {
-            run_days!($matches, $($ds),*)
         }
-    }};
+    }
+
+    if format.is_structured() {
+        println!("{}", serialize(format, &records));
+    } else {
+        println!(
+            "Fetched in {}, parsed in {}, solved {}",
+            format_duration(t.fetch),
+            format_duration(t.parse),
+            solve_times.join(", ")
+        );
+    }
+    match failed {
+        // The errors of the parts have been shown with their results
+        true => Err(Reported.into()),
+        false => Ok(()),
+    }
+}
+
+fn parse_part(part: Option<&str>) -> Part {
+    match part {
+        Some("a") => Part::A,
+        Some("b") => Part::B,
+        _ => unreachable!("No part selected"),
+    }
+}
+
+fn submit(registry: &Registry, year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let day: u8 = matches.value_of("day").unwrap().parse()?;
+    let solver = registry
+        .get(year, day)
+        .ok_or_else(|| format!("No solver registered for day {} of {}", day, year))?;
+    let part = parse_part(matches.value_of("part"));
+    let config = load_config()?;
+    let input: String = get_input(year, day, &config)?;
+    let answer = solver
+        .parse(&input)
+        .and_then(|parsed| solve_part(solver, part, &parsed))
+        .map_err(|e| describe_error(&e, &input))?;
+
+    print_result("Result", &answer);
+    let verdict = submit_answer(year, day, part.level(), &answer, &config)?;
+    println!("{}", verdict);
+    if verdict == Verdict::Right {
+        record_answer(year, day, &config, part, &answer)?;
+    }
+    Ok(())
+}
+
+/// Mark a computed answer as correct for days solved before the ledger existed
+fn record(registry: &Registry, year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let day: u8 = matches.value_of("day").unwrap().parse()?;
+    let solver = registry
+        .get(year, day)
+        .ok_or_else(|| format!("No solver registered for day {} of {}", day, year))?;
+    let part = parse_part(matches.value_of("part"));
+    let config = load_config()?;
+    let input: String = get_input(year, day, &config)?;
+    let answer = solver
+        .parse(&input)
+        .and_then(|parsed| solve_part(solver, part, &parsed))
+        .map_err(|e| describe_error(&e, &input))?;
+
+    print_result("Result", &answer);
+    record_answer(year, day, &config, part, &answer)?;
+    println!("Recorded as the correct answer");
+    Ok(())
+}
+
+fn dashboard(registry: &Registry, year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let solvers: Vec<Solver> = registry.year(year).cloned().collect();
+    if solvers.is_empty() {
+        return Err(format!("No solvers registered for {}", year).into());
+    }
+    let jobs = matches.value_of("jobs").unwrap_or("0").parse()?;
+    run_dashboard(year, solvers, load_config()?, jobs)
+}
+
+fn whoami(year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let config = load_config()?;
+    run_whoami(year, &config, Format::from_matches(matches))
+}
+
+fn read(year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    let day: u8 = matches.value_of("day").unwrap().parse()?;
+    let example = match matches.value_of("example") {
+        Some(n) => Some(n.parse()?),
+        None => None,
+    };
+    let config = load_config()?;
+    read_puzzle(year, day, &config, example, matches.is_present("examples"))
+}
+
+fn scaffold(year: u32, matches: &ArgMatches) -> Result<(), Box<dyn Error>> {
+    // The crates of this repository, and so the template, are those of 2018
+    if year != 2018 {
+        return Err(format!("new-day can only create days of 2018, not {}", year).into());
+    }
+    let day: u8 = matches.value_of("day").unwrap().parse()?;
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    new_day(root, day, matches.value_of("title").unwrap())?;
+    println!("Created day{:02}, rebuild to include it in the runner", day);
+    Ok(())
+}
+
+/// Re-run a day on every change to its crate or input, in a fresh build of the runner
+fn run_watch(
+    registry: &Registry,
+    year: u32,
+    matches: &ArgMatches,
+    mut args: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let day: u8 = matches.value_of("day").unwrap().parse()?;
+    let solver = registry
+        .get(year, day)
+        .ok_or_else(|| format!("No solver registered for day {} of {}", day, year))?;
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let input = match matches.value_of("input") {
+        Some("-") => return Err("The input of watch can't be read from stdin".into()),
+        Some(path) => PathBuf::from(path),
+        None => cache_path(year, day, &load_config()?)?,
+    };
+    let paths = vec![root.join(solver.name()).join("src"), input.clone()];
+
+    args.extend(vec!["--format".into(), "json".into()]);
+    args.extend(vec!["--year".into(), year.to_string(), solver.name()]);
+    if matches.is_present("input") {
+        args.extend(vec!["--input".into(), input.display().to_string()]);
+    }
+    watch(root, &paths, &args)
 }
 
 fn msg_is_slim(msg: &str) -> bool {
     msg.len() <= 10 && !msg.contains('\n')
 }
 
-fn print_result<D: Display>(res: D) {
+/// Describe an error from a solver, pointing out the offending part of the input
+fn describe_error(e: &AoCError, input: &str) -> String {
+    if let AoCError::Parse { line, column, .. } = e {
+        if let Some(text) = line.checked_sub(1).and_then(|i| input.lines().nth(i)) {
+            let marker = format!("{}^", " ".repeat(column.saturating_sub(1)));
+            return format!(
+                "{}\n{:>5} | {}\n      | {}",
+                e,
+                line,
+                style(text).bold(),
+                style(marker).red().bold()
+            );
+        }
+    }
+    e.to_string()
+}
+
+fn hide_long(s: &str) -> &str {
+    if msg_is_slim(s) {
+        s
+    } else {
+        "(...)"
+    }
+}
+
+/// Show a result along with how it compares to the recorded answer
+fn mark_result(answers: &Answers, part: Part, result: &str) -> String {
+    match answers.check(part, result) {
+        Check::Unknown => hide_long(result).to_string(),
+        Check::Correct => format!("{} *", hide_long(result)),
+        Check::Regressed { expected } => format!(
+            "{} REGRESSED, expected {}",
+            hide_long(result),
+            hide_long(&expected)
+        ),
+    }
+}
+
+fn print_result<D: Display>(label: &str, res: D) {
     let s = format!("{}", res);
     if msg_is_slim(&s) {
-        println!("Result: {}", s);
+        println!("{}: {}", label, s);
     } else {
-        println!("Result:\n{}", s);
+        println!("{}:\n{}", label, s);
     }
 }
 
+/// The size of the pool days are solved on
+fn jobs_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("jobs")
+        .long("jobs")
+        .short("j")
+        .takes_value(true)
+        .value_name("N")
+        .validator(|n| match n.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(()),
+            _ => Err("must be a positive number".into()),
+        })
+        .help(
+            "Number of threads to solve days on, defaults to one per CPU. \
+             Parts abandoned by --timeout keep running outside of it until they stop.",
+        )
+}
+
 fn main() {
+    let registry = registry();
+
     let app = app_from_crate!()
         .version(env!("CARGO_PKG_VERSION"))
         .about(env!("CARGO_PKG_DESCRIPTION"))
-        .after_help("Don't forget to set your config.toml!")
-        .subcommand(SubCommand::with_name("all").about("Compute all days"));
+        .after_help(
+            "Don't forget to set your config.toml, or the AOC_SESSION environment variable!",
+        )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .global(true)
+                .takes_value(true)
+                .possible_values(&["human", "json", "ron"])
+                .default_value("human")
+                .help("Output format of the results"),
+        )
+        .arg(
+            Arg::with_name("offline")
+                .long("offline")
+                .global(true)
+                .help("Only use cached inputs, never touch the network"),
+        )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .global(true)
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Read the config from this file instead of looking it up"),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .global(true)
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Use the session of this profile of the config"),
+        )
+        .arg(
+            Arg::with_name("year")
+                .long("year")
+                .global(true)
+                .takes_value(true)
+                .validator(|year| year.parse::<u32>().map(|_| ()).map_err(|e| e.to_string()))
+                .help("The year of the puzzles, defaults to the latest one with solutions"),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .long("timeout")
+                .global(true)
+                .takes_value(true)
+                .value_name("DURATION")
+                .validator(|timeout| parse_duration(&timeout).map(|_| ()))
+                .help("Give up on a part after this long, e.g. 30s"),
+        )
+        .subcommand(
+            SubCommand::with_name("all")
+                .about("Compute all days")
+                .arg(
+                    Arg::with_name("sort-by-time")
+                        .long("sort-by-time")
+                        .help("List the slowest days first in the timing report"),
+                )
+                .arg(
+                    Arg::with_name("days")
+                        .long("days")
+                        .takes_value(true)
+                        .value_name("DAYS")
+                        .help("Only run these days, e.g. 3-7,11"),
+                )
+                .arg(
+                    Arg::with_name("skip")
+                        .long("skip")
+                        .takes_value(true)
+                        .value_name("DAYS")
+                        .help("Leave out these days, e.g. slow ones"),
+                )
+                .arg(jobs_arg())
+                .arg(
+                    Arg::with_name("profiles")
+                        .long("profiles")
+                        .takes_value(true)
+                        .use_delimiter(true)
+                        .value_name("NAMES")
+                        .conflicts_with("sort-by-time")
+                        .help("Run every day for each of these profiles, side by side"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("dashboard")
+                .about("Show every day in a full-screen view, running them on demand")
+                .arg(jobs_arg()),
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .about("Benchmark days on their real input, comparing to a saved baseline")
+                .arg(
+                    Arg::with_name("days")
+                        .multiple(true)
+                        .help("The days to benchmark, e.g. 3-7 11, defaults to all"),
+                )
+                .arg(
+                    Arg::with_name("baseline")
+                        .long("baseline")
+                        .takes_value(true)
+                        .default_value("last")
+                        .help("Name of the baseline to compare against"),
+                )
+                .arg(
+                    Arg::with_name("save-baseline")
+                        .long("save-baseline")
+                        .takes_value(true)
+                        .default_value("last")
+                        .help("Name of the baseline to save the results as"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cache")
+                .about("Manage the cached inputs")
+                .subcommand(SubCommand::with_name("list").about("List the cached inputs"))
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Print the cached input of a day")
+                        .arg(Arg::with_name("day").required(true))
+                        .arg(
+                            Arg::with_name("session")
+                                .long("session")
+                                .takes_value(true)
+                                .help(
+                                    "Prefix of the session digest, defaults to the configured one",
+                                ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("purge")
+                        .about(
+                            "Delete cached inputs and descriptions, keeping the recorded answers",
+                        )
+                        .arg(Arg::with_name("day").long("day").takes_value(true))
+                        .arg(
+                            Arg::with_name("session")
+                                .long("session")
+                                .takes_value(true)
+                                .help("Prefix of the session digest"),
+                        )
+                        .arg(
+                            Arg::with_name("all")
+                                .long("all")
+                                .help("Delete every cached input"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("import")
+                        .about("Use a local file, or stdin if -, as the cached input of a day")
+                        .arg(Arg::with_name("day").required(true))
+                        .arg(Arg::with_name("file").required(true))
+                        .arg(
+                            Arg::with_name("force")
+                                .long("force")
+                                .help("Replace a cached input, deleting its recorded answers"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Inspect the config")
+                .subcommand(
+                    SubCommand::with_name("show")
+                        .about("Print the effective config, with the session masked"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("new-day")
+                .about("Create the crate of a new day from the _dayX template")
+                .arg(Arg::with_name("day").required(true))
+                .arg(
+                    Arg::with_name("title")
+                        .default_value("FIXME")
+                        .help("Title of the puzzle"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("read")
+                .about("Show the puzzle description of a day")
+                .arg(Arg::with_name("day").required(true))
+                .arg(
+                    Arg::with_name("examples")
+                        .long("examples")
+                        .help("Only show the example blocks of the description"),
+                )
+                .arg(
+                    Arg::with_name("example")
+                        .long("example")
+                        .takes_value(true)
+                        .value_name("N")
+                        .conflicts_with("examples")
+                        .help("Print the Nth example block as is, e.g. to pipe into --input -"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("whoami")
+                .about("Check the session and list the unlocked days of the year"),
+        )
+        .subcommand(
+            SubCommand::with_name("watch")
+                .about("Rebuild and re-run a day whenever its crate or input changes")
+                .arg(Arg::with_name("day").required(true))
+                .arg(
+                    Arg::with_name("input")
+                        .long("input")
+                        .short("i")
+                        .takes_value(true)
+                        .value_name("PATH")
+                        .help("Solve and watch this file instead of the downloaded input"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("submit")
+                .about("Compute and submit the answer of a day")
+                .arg(Arg::with_name("day").required(true))
+                .arg(
+                    Arg::with_name("part")
+                        .required(true)
+                        .possible_values(&["a", "b"]),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("record")
+                .about("Record the computed answer of a day as correct")
+                .arg(Arg::with_name("day").required(true))
+                .arg(
+                    Arg::with_name("part")
+                        .required(true)
+                        .possible_values(&["a", "b"]),
+                ),
+        );
 
-    let app = setup_days!(
-        app, Day01, Day02, Day03, Day04, Day05, Day06, Day07, Day08, Day09, Day10, Day11, Day12
-    );
+    let app = setup_days(app, &registry);
 
     let matches = app.get_matches();
-
-    run_days!(
-        matches, all, Day01, Day02, Day03, Day04, Day05, Day06, Day07, Day08, Day09, Day10, Day11,
-        Day12, FIXME
-    );
-}
-
-#[cfg(test)]
-mod test {
-    extern crate test;
-    use self::test::Bencher;
-    use super::*;
-
-    macro_rules! gen_bench {
-        ($fna:ident, $fnb:ident, $d:ident) => {
-            #[bench]
-            fn $fna(b: &mut Bencher) {
-                let input = get_input(2018, stringify!($d)[3..].parse::<u8>().unwrap()).unwrap();
-                b.iter(|| $d::task_a(&input));
-            }
-
-            #[bench]
-            fn $fnb(b: &mut Bencher) {
-                let input = get_input(2018, stringify!($d)[3..].parse::<u8>().unwrap()).unwrap();
-                b.iter(|| $d::task_b(&input));
-            }
-        };
+    let offline = |m: &ArgMatches| m.is_present("offline");
+    FETCH.set_offline(offline(&matches) || matches.subcommand().1.is_some_and(offline));
+    let config = matches
+        .value_of("config")
+        .or_else(|| matches.subcommand().1?.value_of("config"));
+    set_config_path(config.map(PathBuf::from));
+    let profile = matches
+        .value_of("profile")
+        .or_else(|| matches.subcommand().1?.value_of("profile"));
+    set_profile(profile.map(String::from));
+    // What a child process of watch needs to run the same way
+    let mut global_args: Vec<String> = vec![];
+    if FETCH.is_offline() {
+        global_args.push("--offline".into());
+    }
+    if let Some(config) = config {
+        global_args.extend(vec!["--config".into(), config.into()]);
+    }
+    if let Some(profile) = profile {
+        global_args.extend(vec!["--profile".into(), profile.into()]);
+    }
+    let explicit_year: Option<u32> = matches
+        .value_of("year")
+        .or_else(|| matches.subcommand().1?.value_of("year"))
+        .map(|year| year.parse().unwrap());
+    let timeout = matches
+        .value_of("timeout")
+        .or_else(|| matches.subcommand().1?.value_of("timeout"));
+    set_timeout(timeout.map(|timeout| parse_duration(timeout).unwrap()));
+    if let Some(timeout) = timeout {
+        global_args.extend(vec!["--timeout".into(), timeout.into()]);
     }
+    let latest_year = *registry.years().last().expect("No solvers registered");
+    let year = explicit_year.unwrap_or(latest_year);
 
-    gen_bench!(bench_day01_a, bench_day01_b, Day01);
-    gen_bench!(bench_day02_a, bench_day02_b, Day02);
-    gen_bench!(bench_day03_a, bench_day03_b, Day03);
-    gen_bench!(bench_day04_a, bench_day04_b, Day04);
-    gen_bench!(bench_day05_a, bench_day05_b, Day05);
-    gen_bench!(bench_day06_a, bench_day06_b, Day06);
-    gen_bench!(bench_day07_a, bench_day07_b, Day07);
-    gen_bench!(bench_day08_a, bench_day08_b, Day08);
-    gen_bench!(bench_day09_a, bench_day09_b, Day09);
-    gen_bench!(bench_day10_a, bench_day10_b, Day10);
-    gen_bench!(bench_day11_a, bench_day11_b, Day11);
-    gen_bench!(bench_day12_a, bench_day12_b, Day12);
+    let result = match matches.subcommand() {
+        ("all", Some(sub_matches)) => run_all(&registry, year, sub_matches),
+        ("dashboard", Some(sub_matches)) => dashboard(&registry, year, sub_matches),
+        ("bench", Some(sub_matches)) => run_benches(&registry, year, sub_matches),
+        ("cache", Some(sub_matches)) => run_cache(sub_matches, explicit_year, latest_year),
+        ("config", Some(sub_matches)) => match sub_matches.subcommand_name() {
+            Some("show") => show_config(),
+            _ => Err(sub_matches.usage().into()),
+        },
+        ("new-day", Some(sub_matches)) => scaffold(year, sub_matches),
+        ("read", Some(sub_matches)) => read(year, sub_matches),
+        ("whoami", Some(sub_matches)) => whoami(year, sub_matches),
+        ("watch", Some(sub_matches)) => run_watch(&registry, year, sub_matches, global_args),
+        ("submit", Some(sub_matches)) => submit(&registry, year, sub_matches),
+        ("record", Some(sub_matches)) => record(&registry, year, sub_matches),
+        (name, Some(sub_matches)) => match registry.find(year, name) {
+            Some(solver) => run_day(solver, sub_matches),
+            None => Err(format!("No solver registered for {} of {}", name, year).into()),
+        },
+        _ => {
+            println!("{}", matches.usage());
+            Ok(())
+        }
+    };
+    match result {
+        Ok(()) => {}
+        Err(e) if e.is::<Reported>() => process::exit(1),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+    }
 }
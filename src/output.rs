@@ -0,0 +1,127 @@
+use crate::ledger::Answers;
+use aoc_base::{AoCError, Part};
+use clap::ArgMatches;
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How results are written to stdout
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Human,
+    Json,
+    Ron,
+}
+
+impl Format {
+    pub fn from_matches(matches: &ArgMatches) -> Format {
+        match matches.value_of("format") {
+            Some("json") => Format::Json,
+            Some("ron") => Format::Ron,
+            _ => Format::Human,
+        }
+    }
+
+    pub fn is_structured(self) -> bool {
+        self != Format::Human
+    }
+}
+
+/// The outcome of solving one part of a day, in machine-readable form
+#[derive(Debug, Deserialize, PartialEq, Serialize)]
+pub struct Record {
+    /// 0 if the run failed before getting to any day
+    pub day: u8,
+    /// The profile whose input was used, when running several
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// "a" or "b", missing if the day failed before solving either part
+    pub part: Option<String>,
+    pub answer: Option<String>,
+    /// Time spent solving the part, in seconds
+    pub duration: Option<f64>,
+    pub error: Option<String>,
+    /// Whether the answer matches the recorded one, missing if there is none
+    pub matched: Option<bool>,
+}
+
+pub fn part_name(part: Part) -> String {
+    part.to_string().to_lowercase()
+}
+
+impl Record {
+    pub fn new(
+        day: u8,
+        part: Part,
+        result: &Result<String, AoCError>,
+        duration: Duration,
+        answers: Option<&Answers>,
+    ) -> Record {
+        let (answer, error) = match result {
+            Ok(answer) => (Some(answer.clone()), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let matched = answer
+            .as_ref()
+            .and_then(|answer| answers?.check(part, answer).matched());
+        Record {
+            day,
+            profile: None,
+            part: Some(part_name(part)),
+            answer,
+            duration: Some(duration.as_secs_f64()),
+            error,
+            matched,
+        }
+    }
+
+    pub fn failure(day: u8, part: Option<Part>, error: String) -> Record {
+        Record {
+            day,
+            profile: None,
+            part: part.map(part_name),
+            answer: None,
+            duration: None,
+            error: Some(error),
+            matched: None,
+        }
+    }
+}
+
+/// Serialize a record, or a list of them, in a structured format
+pub fn serialize<T: serde::Serialize>(format: Format, value: &T) -> String {
+    match format {
+        Format::Json => serde_json::to_string(value).expect("Failed to serialize as JSON"),
+        Format::Ron => ron::ser::to_string(value).expect("Failed to serialize as RON"),
+        Format::Human => unreachable!("Human readable output isn't serialized"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize() {
+        let answers = Answers {
+            a: Some("3".into()),
+            b: None,
+        };
+        let record = Record::new(
+            1,
+            Part::A,
+            &Ok("3".into()),
+            Duration::from_millis(5),
+            Some(&answers),
+        );
+        assert_eq!(
+            serialize(Format::Json, &record),
+            r#"{"day":1,"part":"a","answer":"3","duration":0.005,"error":null,"matched":true}"#
+        );
+
+        let record = Record::failure(2, None, "No input".into());
+        assert_eq!(
+            serialize(Format::Json, &record),
+            r#"{"day":2,"part":null,"answer":null,"duration":null,"error":"No input","matched":null}"#
+        );
+    }
+}
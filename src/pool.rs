@@ -0,0 +1,79 @@
+//! A fixed number of threads running days in the order they're queued.
+//!
+//! Days don't run on the rayon pool, since a rayon worker waiting on a join
+//! may pick up a whole other day and run it inline, adding its time to the
+//! part it was solving. The parallel parts of days share the global rayon
+//! pool instead, sized to the same number of threads.
+
+use rayon::ThreadPoolBuilder;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Dropping the pool lets the queued jobs finish, without waiting for them
+pub struct Pool {
+    queue: Sender<Job>,
+}
+
+fn work(queue: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+        match job {
+            // Jobs report their own panics, this only keeps the thread alive
+            Ok(job) => drop(panic::catch_unwind(AssertUnwindSafe(job))),
+            Err(_) => return,
+        }
+    }
+}
+
+impl Pool {
+    /// A pool of `threads` threads, or one per CPU if 0
+    pub fn new(threads: usize) -> Pool {
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        // Only the first pool gets to size the global rayon pool
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .ok();
+
+        let (tx, rx) = channel();
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..threads {
+            let rx = rx.clone();
+            thread::spawn(move || work(&rx));
+        }
+        Pool { queue: tx }
+    }
+
+    pub fn spawn<F: FnOnce() + Send + 'static>(&self, job: F) {
+        self.queue
+            .send(Box::new(job))
+            .expect("Every thread of the pool has died");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool() {
+        let pool = Pool::new(2);
+        let (tx, rx) = channel();
+        pool.spawn(|| panic!("A job panicked"));
+        for i in 0..4 {
+            let tx = tx.clone();
+            pool.spawn(move || tx.send(i).unwrap());
+        }
+        drop(tx);
+        let mut done: Vec<i32> = rx.iter().collect();
+        done.sort();
+        assert_eq!(done, vec![0, 1, 2, 3]);
+    }
+}
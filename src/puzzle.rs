@@ -0,0 +1,419 @@
+//! Puzzle descriptions, downloaded from the page of a day and cached next to
+//! its input as `<session>.puzzle`, holding the `<article>` of each unlocked part.
+
+use crate::cache::{cache_path, write_atomic};
+use crate::config::Config;
+use crate::fetch::FETCH;
+use crate::input::FetchError;
+use console::{style, Term};
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+/// Paragraphs are wrapped to the terminal, but never wider than this
+const MAX_WIDTH: usize = 80;
+
+pub fn puzzle_url(year: u32, day: u8, config: &Config) -> String {
+    format!("{}/{}/day/{}", config.url, year, day)
+}
+
+fn puzzle_path(year: u32, day: u8, config: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(cache_path(year, day, config)?.with_extension("puzzle"))
+}
+
+/// The `<article>` sections of a page, one for each unlocked part
+fn extract_articles(html: &str) -> Vec<&str> {
+    let mut articles = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find("<article") {
+        let end = match rest[start..].find("</article>") {
+            Some(end) => start + end + "</article>".len(),
+            None => break,
+        };
+        articles.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    articles
+}
+
+/// Make sure a response of the puzzle page holds a description
+fn check_page(status: u16, body: &str) -> Result<(), FetchError> {
+    match status {
+        200 if extract_articles(body).is_empty() => Err(FetchError::Unexpected(
+            "no puzzle description on the page".into(),
+        )),
+        200 => Ok(()),
+        404 => Err(FetchError::NotUnlocked),
+        500..=599 => Err(FetchError::Server(status)),
+        _ => Err(FetchError::Unexpected(format!("HTTP {}", status))),
+    }
+}
+
+fn download_puzzle(year: u32, day: u8, config: &Config) -> Result<String, Box<dyn Error>> {
+    let url = puzzle_url(year, day, config);
+    let mut resp = FETCH.send(true, |client| {
+        client
+            .get(&url)
+            .header("cookie", format!("session={}", config.session))
+    })?;
+    let body = resp.text()?;
+    check_page(resp.status().as_u16(), &body)?;
+    Ok(extract_articles(&body).concat())
+}
+
+/// The articles of a puzzle, from the cache unless part 2 might have been unlocked since
+pub fn get_puzzle(year: u32, day: u8, config: &Config) -> Result<Vec<String>, Box<dyn Error>> {
+    let path = puzzle_path(year, day, config)?;
+    let cached = fs::read_to_string(&path).ok();
+    let articles = |html: &str| {
+        extract_articles(html)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    };
+
+    if let Some(cached) = &cached {
+        if extract_articles(cached).len() >= 2 {
+            return Ok(articles(cached));
+        }
+    }
+    match download_puzzle(year, day, config) {
+        Ok(html) => {
+            write_atomic(&path, html.as_bytes())?;
+            Ok(articles(&html))
+        }
+        // Part 1 is better than nothing, e.g. when offline
+        Err(_) if cached.is_some() => Ok(articles(&cached.unwrap())),
+        Err(e) => Err(format!("Could not fetch the puzzle of day {}: {}", day, e).into()),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open(&'a str, &'a str),
+    Close(&'a str),
+    Text(String),
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) if end <= 8 => end,
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+                continue;
+            }
+        };
+        let decoded = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16)
+                .ok()
+                .and_then(std::char::from_u32),
+            entity if entity.starts_with('#') => {
+                entity[1..].parse().ok().and_then(std::char::from_u32)
+            }
+            _ => None,
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split html into tags and text, dropping comments
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut rest = html;
+    while !rest.is_empty() {
+        if rest.starts_with("<!--") {
+            rest = rest.find("-->").map(|end| &rest[end + 3..]).unwrap_or("");
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').unwrap_or(rest.len() - 1);
+            let tag = rest[1..end].trim_end_matches('/');
+            let (name, attrs) = tag.split_at(tag.find(' ').unwrap_or(tag.len()));
+            match name.strip_prefix('/') {
+                Some(name) => tokens.push(Token::Close(name)),
+                None => tokens.push(Token::Open(name, attrs.trim())),
+            }
+            rest = &rest[end + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(decode_entities(&rest[..end])));
+            rest = &rest[end..];
+        }
+    }
+    tokens
+}
+
+/// How a piece of inline text is shown
+#[derive(Clone, Copy, Default, PartialEq)]
+struct Inline {
+    emphasis: bool,
+    star: bool,
+    code: bool,
+    link: bool,
+}
+
+impl Inline {
+    fn paint(self, text: &str) -> String {
+        let mut styled = style(text);
+        if self.code {
+            styled = styled.cyan();
+        }
+        if self.emphasis {
+            styled = styled.bold().white();
+        }
+        if self.star {
+            styled = styled.bold().yellow();
+        }
+        if self.link {
+            styled = styled.underlined();
+        }
+        styled.to_string()
+    }
+}
+
+/// Wrap styled text to `width` columns, starting the first line with `bullet`
+/// and indenting the others to match
+fn wrap(fragments: &[(String, Inline)], width: usize, bullet: &str, out: &mut String) {
+    let indent = " ".repeat(bullet.len());
+    // Words are runs of fragments between whitespace, kept together across styles
+    let mut words: Vec<Vec<(&str, Inline)>> = vec![];
+    let mut in_word = false;
+    for (text, inline) in fragments {
+        let mut rest = text.as_str();
+        while !rest.is_empty() {
+            if rest.starts_with(char::is_whitespace) {
+                in_word = false;
+                rest = rest.trim_start();
+                continue;
+            }
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            if !in_word {
+                words.push(vec![]);
+                in_word = true;
+            }
+            words.last_mut().unwrap().push((&rest[..end], *inline));
+            rest = &rest[end..];
+        }
+    }
+
+    let mut column = 0;
+    let mut first = true;
+    for word in words {
+        let len: usize = word.iter().map(|(text, _)| text.chars().count()).sum();
+        if column > 0 && column + 1 + len > width {
+            out.push('\n');
+            column = 0;
+        }
+        if column == 0 {
+            out.push_str(if first { bullet } else { &indent });
+            first = false;
+            column = bullet.len();
+        } else {
+            out.push(' ');
+            column += 1;
+        }
+        for (text, inline) in word {
+            out.push_str(&inline.paint(text));
+        }
+        column += len;
+    }
+    if column > 0 {
+        out.push('\n');
+    }
+}
+
+/// Render the html of an article as terminal text
+fn render(html: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut inline = Inline::default();
+    let mut fragments: Vec<(String, Inline)> = vec![];
+    let mut pre: Option<String> = None;
+    let mut item = false;
+
+    for token in tokenize(html) {
+        match token {
+            Token::Text(text) => match &mut pre {
+                Some(pre) => pre.push_str(&text),
+                None => fragments.push((text, inline)),
+            },
+            Token::Open("pre", _) => pre = Some(String::new()),
+            Token::Close("pre") => {
+                for line in pre.take().unwrap_or_default().trim_end().lines() {
+                    out.push_str(&format!("    {}\n", style(line).cyan()));
+                }
+                out.push('\n');
+            }
+            Token::Open("em", attrs) if attrs.contains("star") => inline.star = true,
+            Token::Open("em", _) => inline.emphasis = true,
+            Token::Close("em") => {
+                inline.emphasis = false;
+                inline.star = false;
+            }
+            Token::Open("code", _) => inline.code = true,
+            Token::Close("code") => inline.code = false,
+            Token::Open("a", _) => inline.link = true,
+            Token::Close("a") => inline.link = false,
+            Token::Open("li", _) => item = true,
+            Token::Close("h2") => {
+                let text: String = fragments.drain(..).map(|(text, _)| text).collect();
+                out.push_str(&format!("{}\n\n", style(text.trim()).bold()));
+            }
+            Token::Close("p") | Token::Close("li") => {
+                let indent = if item { "  - " } else { "" };
+                wrap(&fragments, width, indent, &mut out);
+                fragments.clear();
+                if item {
+                    item = false;
+                } else {
+                    out.push('\n');
+                }
+            }
+            Token::Close("ul") => out.push('\n'),
+            _ => {}
+        }
+    }
+    wrap(&fragments, width, "", &mut out);
+    out
+}
+
+/// The example blocks of a description, as plain text
+pub fn examples(articles: &[String]) -> Vec<String> {
+    let mut found = vec![];
+    for article in articles {
+        let mut pre: Option<String> = None;
+        for token in tokenize(article) {
+            match token {
+                Token::Open("pre", _) => pre = Some(String::new()),
+                Token::Close("pre") => found.extend(pre.take()),
+                Token::Text(text) => {
+                    if let Some(pre) = &mut pre {
+                        pre.push_str(&text);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    found
+}
+
+/// Print the description of a day, or its examples
+pub fn read_puzzle(
+    year: u32,
+    day: u8,
+    config: &Config,
+    example: Option<usize>,
+    list_examples: bool,
+) -> Result<(), Box<dyn Error>> {
+    let articles = get_puzzle(year, day, config)?;
+
+    if let Some(n) = example {
+        let examples = examples(&articles);
+        let example = n
+            .checked_sub(1)
+            .and_then(|i| examples.get(i))
+            .ok_or_else(|| format!("There are {} examples", examples.len()))?;
+        print!("{}", example);
+        return Ok(());
+    }
+    if list_examples {
+        for (i, example) in examples(&articles).iter().enumerate() {
+            println!("{}", style(format!("Example {}:", i + 1)).bold());
+            print!("{}", example);
+            println!();
+        }
+        return Ok(());
+    }
+
+    let width = Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
+        .unwrap_or(MAX_WIDTH)
+        .min(MAX_WIDTH);
+    for article in &articles {
+        print!("{}", render(article, width));
+    }
+    if articles.len() < 2 {
+        println!("{}", style("Part 2 unlocks once part 1 is solved").dim());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use console::strip_ansi_codes;
+
+    const PAGE: &str = "<html><main>\
+        <article class=\"day-desc\"><h2>--- Day 1: Chronal Calibration ---</h2>\
+        <p>For example, if the device displays frequency changes of <code>+1, -2</code>, \
+        starting with a frequency of <code>0</code>, the following changes would occur:</p>\
+        <pre><code>+1\n-2\n</code></pre>\
+        <ul><li>Current frequency <em>&lt;0&gt;</em>, change of <code>+1</code>.</li></ul>\
+        </article>\
+        <p>Your puzzle answer was <code>3</code>.</p>\
+        <article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2>\
+        <p>What is the first frequency reached <em>twice</em>?</p>\
+        <pre><code>+3\n+3\n+4\n-2\n-4\n</code></pre>\
+        </article></main></html>";
+
+    #[test]
+    fn test_check_page() {
+        assert_eq!(check_page(200, PAGE), Ok(()));
+        assert_eq!(check_page(404, ""), Err(FetchError::NotUnlocked));
+        assert_eq!(check_page(502, ""), Err(FetchError::Server(502)));
+        assert!(check_page(200, "<html>Log in</html>").is_err());
+        assert_eq!(extract_articles(PAGE).len(), 2);
+    }
+
+    #[test]
+    fn test_render() {
+        let articles = extract_articles(PAGE);
+        let text = render(articles[0], 40);
+        assert_eq!(
+            strip_ansi_codes(&text),
+            "--- Day 1: Chronal Calibration ---\n\n\
+             For example, if the device displays\n\
+             frequency changes of +1, -2, starting\n\
+             with a frequency of 0, the following\n\
+             changes would occur:\n\n    \
+             +1\n    \
+             -2\n\n  \
+             - Current frequency <0>, change of +1.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_examples() {
+        let articles: Vec<String> = extract_articles(PAGE)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(
+            examples(&articles),
+            vec!["+1\n-2\n", "+3\n+3\n+4\n-2\n-4\n"]
+        );
+        assert_eq!(decode_entities("a &amp;&#60; &x; b&"), "a &< &x; b&");
+    }
+}
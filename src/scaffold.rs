@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Directory of the template crate, relative to the repository root
+const TEMPLATE: &str = "_dayX";
+
+/// Insert `line` among the lines starting with `prefix`, keeping them sorted
+fn insert_sorted(text: &str, prefix: &str, line: &str) -> Result<String, Box<dyn Error>> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let last = lines
+        .iter()
+        .rposition(|l| l.trim_start().starts_with(prefix))
+        .ok_or_else(|| format!("Found no line starting with {:?}", prefix))?;
+    let at = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with(prefix) && l.trim_start() > line.trim_start())
+        .unwrap_or(last + 1);
+    lines.insert(at, line);
+
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Ok(text)
+}
+
+/// Copy the template, replacing its placeholders
+fn instantiate(from: &Path, to: &Path, day: u8, title: &str) -> Result<(), Box<dyn Error>> {
+    fs::create_dir(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if name == "target" || name == "Cargo.lock" {
+            continue;
+        }
+
+        let path = entry.path();
+        if path.is_dir() {
+            instantiate(&path, &to.join(&name), day, title)?;
+        } else {
+            let text = fs::read_to_string(&path)?
+                .replace("aoc_2018_dayXX", &format!("aoc_2018_day{:02}", day))
+                .replace("DayX", &format!("Day{:02}", day))
+                .replace("0 /* FIXME */", &day.to_string())
+                .replace("\"FIXME\"", &format!("{:?}", title));
+            fs::write(to.join(&name), text)?;
+        }
+    }
+    Ok(())
+}
+
+/// Create the crate of a new day from the template and add it to the runner
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<(), Box<dyn Error>> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("There is no day {}", day).into());
+    }
+    let name = format!("day{:02}", day);
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()).into());
+    }
+
+    // Prepare every edit before touching anything
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = insert_sorted(
+        &fs::read_to_string(&manifest_path)?,
+        "aoc_2018_day",
+        &format!("aoc_2018_{} = {{ path=\"{}\" }}", name, name),
+    )?;
+    let main_path = root.join("src").join("main.rs");
+    let main = insert_sorted(
+        &fs::read_to_string(&main_path)?,
+        "aoc_2018_day",
+        &format!("    aoc_2018_{}::register(&mut registry);", name),
+    )?;
+
+    instantiate(&root.join(TEMPLATE), &dir, day, title)?;
+    fs::write(manifest_path, manifest)?;
+    fs::write(main_path, main)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_insert_sorted() {
+        let text = "a = 1\nday01 = 1\nday03 = 3\nb = 2\n";
+        assert_eq!(
+            insert_sorted(text, "day", "day02 = 2").unwrap(),
+            "a = 1\nday01 = 1\nday02 = 2\nday03 = 3\nb = 2\n"
+        );
+        assert_eq!(
+            insert_sorted(text, "day", "day04 = 4").unwrap(),
+            "a = 1\nday01 = 1\nday03 = 3\nday04 = 4\nb = 2\n"
+        );
+        assert!(insert_sorted(text, "night", "night01").is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let root = env::temp_dir().join(format!("aoc_new_day_{}", std::process::id()));
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::create_dir_all(root.join(TEMPLATE).join("src")).unwrap();
+        for file in &[
+            "Cargo.toml",
+            "src/main.rs",
+            "_dayX/Cargo.toml",
+            "_dayX/src/lib.rs",
+        ] {
+            fs::copy(src.join(file), root.join(file)).unwrap();
+        }
+
+        new_day(&root, 24, "Immune System Simulator 20XX").unwrap();
+        let lib = fs::read_to_string(root.join("day24/src/lib.rs")).unwrap();
+        assert!(lib.contains("pub struct Day24;"));
+        assert!(lib.contains("register::<Day24, _, _>(2018, 24, \"Immune System Simulator 20XX\")"));
+        let manifest = fs::read_to_string(root.join("day24/Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"aoc_2018_day24\""));
+        let main = fs::read_to_string(root.join("src/main.rs")).unwrap();
+        assert!(main.contains("aoc_2018_day24::register(&mut registry);"));
+        let root_manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        assert!(root_manifest.contains("aoc_2018_day24 = { path=\"day24\" }"));
+
+        assert!(new_day(&root, 24, "Again").is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
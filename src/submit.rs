@@ -0,0 +1,160 @@
+use crate::config::Config;
+use crate::fetch::FETCH;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+/// The server's judgement of a submitted answer
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited(Duration),
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Verdict::Right => write!(f, "That's the right answer!"),
+            Verdict::Wrong => write!(f, "That's not the right answer."),
+            Verdict::TooHigh => write!(f, "That's not the right answer, it's too high."),
+            Verdict::TooLow => write!(f, "That's not the right answer, it's too low."),
+            Verdict::RateLimited(wait) => write!(
+                f,
+                "You gave an answer too recently, wait {}s before trying again.",
+                wait.as_secs()
+            ),
+        }
+    }
+}
+
+/// Post an answer for the given level (1 or 2) of a day
+pub fn submit_answer(
+    year: u32,
+    day: u8,
+    level: u8,
+    answer: &str,
+    config: &Config,
+) -> Result<Verdict, Box<dyn Error>> {
+    let url = format!("{}/{}/day/{}/answer", config.url, year, day);
+    let level = level.to_string();
+    // Never retry, a repeated submission could count as another wrong answer
+    let mut resp = FETCH.send(false, |client| {
+        client
+            .post(&url)
+            .header("cookie", format!("session={}", config.session))
+            .form(&[("level", level.as_str()), ("answer", answer)])
+    })?;
+    parse_response(&resp.text()?)
+}
+
+fn parse_response(html: &str) -> Result<Verdict, Box<dyn Error>> {
+    // The verdict is the only <article> on the page
+    let text = match (html.find("<article>"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+
+    if text.contains("That's the right answer") {
+        Ok(Verdict::Right)
+    } else if text.contains("That's not the right answer") {
+        if text.contains("too high") {
+            Ok(Verdict::TooHigh)
+        } else if text.contains("too low") {
+            Ok(Verdict::TooLow)
+        } else {
+            Ok(Verdict::Wrong)
+        }
+    } else if text.contains("You gave an answer too recently") {
+        let wait = parse_wait_time(text).ok_or("Could not parse remaining wait time")?;
+        Ok(Verdict::RateLimited(wait))
+    } else {
+        Err("Unrecognized response from server".into())
+    }
+}
+
+/// Parse a phrase like "You have 1m 23s left to wait"
+fn parse_wait_time(text: &str) -> Option<Duration> {
+    let start = text.find("You have ")? + "You have ".len();
+    let end = start + text[start..].find(" left to wait")?;
+
+    let mut seconds = 0;
+    for part in text[start..end].split_whitespace() {
+        let (n, unit) = part.split_at(part.len() - 1);
+        let n: u64 = n.parse().ok()?;
+        seconds += match unit {
+            "h" => n * 60 * 60,
+            "m" => n * 60,
+            "s" => n,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve_config;
+
+    fn page(article: &str) -> String {
+        format!(
+            "<html><body><main><article><p>{}</p></article></main></body></html>",
+            article
+        )
+    }
+
+    #[test]
+    fn test_submit() {
+        let (config, rx) = serve_config(
+            200,
+            "<html><main><article><p>That's the right answer! \
+             You are one gold star closer to fixing the time stream.</p></article></main></html>",
+        );
+        let verdict = submit_answer(2018, 7, 2, "CABDFE", &config).unwrap();
+        assert_eq!(verdict, Verdict::Right);
+
+        let request = rx.recv().unwrap();
+        assert!(request.starts_with("POST /2018/day/7/answer "));
+        assert!(request.contains("session=abc123"));
+        assert!(request.ends_with("level=2&answer=CABDFE"));
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let cases = vec![
+            (
+                "That's the right answer! You are one gold star closer.",
+                Verdict::Right,
+            ),
+            (
+                "That's not the right answer. If you're stuck, make sure you're using the full input data.",
+                Verdict::Wrong,
+            ),
+            (
+                "That's not the right answer; your answer is too high. Please wait one minute before trying again.",
+                Verdict::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Verdict::TooLow,
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 37s left to wait.",
+                Verdict::RateLimited(Duration::from_secs(37)),
+            ),
+            (
+                "You gave an answer too recently. You have 4m 2s left to wait.",
+                Verdict::RateLimited(Duration::from_secs(242)),
+            ),
+        ];
+
+        for (article, verdict) in cases {
+            assert_eq!(parse_response(&page(article)).unwrap(), verdict);
+        }
+        assert!(parse_response(&page("Something else entirely")).is_err());
+    }
+}
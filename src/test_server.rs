@@ -0,0 +1,60 @@
+//! A stub of the Advent of Code server for the tests of the modules talking to it
+
+use crate::config::Config;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+/// Answer one request with each `(status, body)`, in order, passing on the
+/// raw requests. Returns the url of the server.
+pub fn serve(responses: Vec<(u16, &'static str)>) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.to_lowercase().starts_with("content-length:") {
+                    content_length = line[15..].trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            // The test may not care about the request
+            tx.send(request).ok();
+        }
+    });
+    (url, rx)
+}
+
+/// A config pointing at a server answering a single request with `status` and `body`
+pub fn serve_config(status: u16, body: &'static str) -> (Config, Receiver<String>) {
+    let (url, rx) = serve(vec![(status, body)]);
+    let config = Config {
+        url,
+        session: "abc123".into(),
+    };
+    (config, rx)
+}
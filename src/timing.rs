@@ -0,0 +1,116 @@
+use std::time::{Duration, Instant};
+
+/// Wall-clock durations of the phases of running one day
+#[derive(Clone, Copy, Default)]
+pub struct Timings {
+    pub fetch: Duration,
+    pub parse: Duration,
+    pub part_a: Duration,
+    pub part_b: Duration,
+}
+
+impl Timings {
+    /// Time spent in the solver itself, excluding fetching the input
+    pub fn solve(&self) -> Duration {
+        self.parse + self.part_a + self.part_b
+    }
+}
+
+/// Run `f`, storing its wall-clock duration in `duration`
+pub fn timed<T, F: FnOnce() -> T>(duration: &mut Duration, f: F) -> T {
+    let start = Instant::now();
+    let result = f();
+    *duration = start.elapsed();
+    result
+}
+
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs() as f64 + f64::from(d.subsec_nanos()) * 1e-9;
+    if secs >= 1.0 {
+        format!("{:.2}s", secs)
+    } else if secs >= 1e-3 {
+        format!("{:.2}ms", secs * 1e3)
+    } else {
+        format!("{:.2}µs", secs * 1e6)
+    }
+}
+
+/// Parse a duration like "30s", "500ms" or "2m", where a bare number is seconds
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("{:?} is not a duration, e.g. 30s", text))?;
+    let secs = match unit {
+        "" | "s" => number,
+        "ms" => number / 1e3,
+        "m" => number * 60.0,
+        _ => return Err(format!("Unknown unit {:?}, use ms, s or m", unit)),
+    };
+    Duration::try_from_secs_f64(secs).map_err(|_| format!("{:?} is too long", text))
+}
+
+/// Print a table of the timings of every day, followed by the total runtime
+pub fn print_report(mut rows: Vec<(String, Option<Timings>)>, sort_by_time: bool, wall: Duration) {
+    if sort_by_time {
+        rows.sort_by_key(|(_, t)| std::cmp::Reverse(t.map(|t| t.solve())));
+    }
+
+    println!(
+        "{:6} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Day", "Fetch", "Parse", "A", "B", "Solve"
+    );
+
+    let mut sum = Timings::default();
+    for (name, timings) in rows {
+        match timings {
+            Some(t) => {
+                println!(
+                    "{:6} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                    name,
+                    format_duration(t.fetch),
+                    format_duration(t.parse),
+                    format_duration(t.part_a),
+                    format_duration(t.part_b),
+                    format_duration(t.solve())
+                );
+                sum.fetch += t.fetch;
+                sum.parse += t.parse;
+                sum.part_a += t.part_a;
+                sum.part_b += t.part_b;
+            }
+            None => println!("{:6} {:>10}", name, "failed"),
+        }
+    }
+
+    println!(
+        "{:6} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "Sum",
+        format_duration(sum.fetch),
+        format_duration(sum.parse),
+        format_duration(sum.part_a),
+        format_duration(sum.part_b),
+        format_duration(sum.solve())
+    );
+    println!("Total runtime: {}", format_duration(wall));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert!(parse_duration("2h").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("99999999999999999999999").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! Re-run a day whenever its crate or input changes. The sources are polled,
+//! since the runner itself has to be rebuilt to pick up a change, which is
+//! left to `cargo run` in a child process.
+
+use crate::output::Record;
+use crate::timing::format_duration;
+use console::style;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Modification times of every watched file, missing files left out
+type Snapshot = BTreeMap<PathBuf, SystemTime>;
+
+fn add_files(path: &Path, snapshot: &mut Snapshot) {
+    match fs::metadata(path) {
+        Ok(meta) if meta.is_dir() => {
+            for entry in fs::read_dir(path).into_iter().flatten().flatten() {
+                add_files(&entry.path(), snapshot);
+            }
+        }
+        Ok(meta) => {
+            if let Ok(modified) = meta.modified() {
+                snapshot.insert(path.to_owned(), modified);
+            }
+        }
+        Err(_) => {}
+    }
+}
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+    for path in paths {
+        add_files(path, &mut snapshot);
+    }
+    snapshot
+}
+
+/// The files that were added, removed or modified between two snapshots
+fn changes<'a>(old: &'a Snapshot, new: &'a Snapshot) -> Vec<&'a Path> {
+    let mut changed: Vec<&Path> = new
+        .iter()
+        .filter(|(path, modified)| old.get(*path) != Some(modified))
+        .map(|(path, _)| path.as_path())
+        .collect();
+    changed.extend(
+        old.keys()
+            .filter(|path| !new.contains_key(*path))
+            .map(|path| path.as_path()),
+    );
+    changed
+}
+
+/// How a part compares to the previous run
+fn describe(record: &Record, previous: Option<&Record>) -> String {
+    let part = record.part.as_deref().unwrap_or("?").to_uppercase();
+    let answer = match (&record.answer, &record.error) {
+        (Some(answer), _) => answer.clone(),
+        (None, Some(error)) => format!("error: {}", error),
+        (None, None) => "-".to_string(),
+    };
+    let duration = record
+        .duration
+        .map(|secs| format_duration(Duration::from_secs_f64(secs)))
+        .unwrap_or_default();
+    let previous = match previous {
+        Some(previous) => previous,
+        None => return format!("{}: {}   in {}", part, answer, duration),
+    };
+
+    let change = if previous.answer == record.answer {
+        style("unchanged".to_string()).dim()
+    } else {
+        let was = match (&previous.answer, &previous.error) {
+            (Some(answer), _) => answer.clone(),
+            _ => "an error".to_string(),
+        };
+        style(format!("changed, was {}", was)).yellow().bold()
+    };
+    let was = previous
+        .duration
+        .map(|secs| format!(" (was {})", format_duration(Duration::from_secs_f64(secs))))
+        .unwrap_or_default();
+    format!("{}: {}   {}   in {}{}", part, answer, change, duration, was)
+}
+
+/// Build and run the day in a child process, returning its records
+fn run_once(root: &Path, args: &[String]) -> Result<Vec<Record>, Box<dyn Error>> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut command = Command::new(cargo);
+    command
+        .arg("run")
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(root.join("Cargo.toml"));
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    // Compiler errors are passed through to the terminal
+    let output = command.arg("--").args(args).output()?;
+    eprint!("{}", String::from_utf8_lossy(&output.stderr));
+    // The runner also exits with an error when a part fails, but only a
+    // failed build leaves nothing on stdout
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() && stdout.trim().is_empty() {
+        return Err("Build failed".into());
+    }
+
+    serde_json::from_str(&stdout)
+        .map_err(|e| format!("Unexpected output of the runner: {}", e).into())
+}
+
+/// Watch `paths`, re-running the day with `args` on every change
+pub fn watch(root: &Path, paths: &[PathBuf], args: &[String]) -> Result<(), Box<dyn Error>> {
+    for path in paths {
+        println!("Watching {}", path.display());
+    }
+    let mut previous: Vec<Record> = vec![];
+    loop {
+        let start = Instant::now();
+        match run_once(root, args) {
+            Ok(records) => {
+                for record in &records {
+                    let before = previous.iter().find(|r| r.part == record.part);
+                    println!("{}", describe(record, before));
+                }
+                previous = records;
+            }
+            Err(e) => eprintln!("Error: {}", e),
+        }
+        println!(
+            "{}",
+            style(format!(
+                "Done in {}, waiting for changes...",
+                format_duration(start.elapsed())
+            ))
+            .dim()
+        );
+
+        // Taken after the run, which may have downloaded the input
+        let seen = snapshot(paths);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = snapshot(paths);
+            let changed: Vec<String> = changes(&seen, &current)
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            if !changed.is_empty() {
+                println!();
+                println!("Changed: {}", changed.join(", "));
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn test_changes() {
+        let dir = env::temp_dir().join(format!("aoc_watch_{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        let lib = dir.join("src").join("lib.rs");
+        let input = dir.join("input");
+        fs::write(&lib, "a").unwrap();
+        let paths = [dir.join("src"), input.clone()];
+
+        let before = snapshot(&paths);
+        assert_eq!(before.keys().collect::<Vec<_>>(), vec![&lib]);
+        assert!(changes(&before, &snapshot(&paths)).is_empty());
+
+        fs::write(&input, "1").unwrap();
+        let after = snapshot(&paths);
+        assert_eq!(changes(&before, &after), vec![input.as_path()]);
+
+        fs::remove_file(&lib).unwrap();
+        assert_eq!(changes(&after, &snapshot(&paths)), vec![lib.as_path()]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_describe() {
+        let record = |answer: &str| Record {
+            day: 1,
+            profile: None,
+            part: Some("a".into()),
+            answer: Some(answer.into()),
+            duration: Some(0.002),
+            error: None,
+            matched: None,
+        };
+        assert_eq!(describe(&record("3"), None), "A: 3   in 2.00ms");
+        let unchanged = describe(&record("3"), Some(&record("3")));
+        assert!(unchanged.contains("unchanged"));
+        let changed = describe(&record("4"), Some(&record("3")));
+        assert!(changed.contains("changed, was 3"));
+        assert!(changed.ends_with("in 2.00ms (was 2.00ms)"));
+    }
+}